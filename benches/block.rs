@@ -0,0 +1,34 @@
+//! Benchmarks of block parsing on long extended blocks. Time per line should stay the same for all sizes.
+
+#[macro_use]
+extern crate bencher;
+extern crate textile;
+
+use bencher::Bencher;
+
+/// Builds extended block with `count` paragraphs after signature.
+fn bench_block(bench: &mut Bencher, signature: &str, count: usize) {
+    let text = format!("{} First line\n{}", signature, "Next *line*\n\n".repeat(count));
+
+    bench.bytes = text.len() as u64;
+    bench.iter(|| textile::parser::parse_borrowed(&text));
+}
+
+fn code_block_1k(bench: &mut Bencher) {
+    bench_block(bench, "bc..", 1000);
+}
+
+fn code_block_16k(bench: &mut Bencher) {
+    bench_block(bench, "bc..", 16 * 1000);
+}
+
+fn block_quotation_1k(bench: &mut Bencher) {
+    bench_block(bench, "bq..", 1000);
+}
+
+fn block_quotation_16k(bench: &mut Bencher) {
+    bench_block(bench, "bq..", 16 * 1000);
+}
+
+benchmark_group!(benches, code_block_1k, code_block_16k, block_quotation_1k, block_quotation_16k);
+benchmark_main!(benches);
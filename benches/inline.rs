@@ -0,0 +1,46 @@
+//! Benchmarks of inline parsing on long lines. Time per byte should stay the same for all sizes.
+
+#[macro_use]
+extern crate bencher;
+extern crate textile;
+
+use bencher::Bencher;
+
+/// Builds line of `size` bytes from repeated pattern.
+fn line(pattern: &str, size: usize) -> String {
+    pattern.repeat(size / pattern.len())
+}
+
+fn bench_line(bench: &mut Bencher, pattern: &str, size: usize) {
+    let text = line(pattern, size);
+
+    bench.bytes = text.len() as u64;
+    bench.iter(|| textile::parser::parse_borrowed(&text));
+}
+
+fn words_64k(bench: &mut Bencher) {
+    bench_line(bench, "lorem ipsum dolor ", 64 * 1024);
+}
+
+fn words_1m(bench: &mut Bencher) {
+    bench_line(bench, "lorem ipsum dolor ", 1024 * 1024);
+}
+
+fn phrases_64k(bench: &mut Bencher) {
+    bench_line(bench, "*bold _italic_* @code@ ", 64 * 1024);
+}
+
+fn phrases_1m(bench: &mut Bencher) {
+    bench_line(bench, "*bold _italic_* @code@ ", 1024 * 1024);
+}
+
+fn unclosed_64k(bench: &mut Bencher) {
+    bench_line(bench, "*a \"b [c ABC(d ==e ", 64 * 1024);
+}
+
+fn unclosed_1m(bench: &mut Bencher) {
+    bench_line(bench, "*a \"b [c ABC(d ==e ", 1024 * 1024);
+}
+
+benchmark_group!(benches, words_64k, words_1m, phrases_64k, phrases_1m, unclosed_64k, unclosed_1m);
+benchmark_main!(benches);
@@ -0,0 +1,253 @@
+/// Typographic glyphs, which replace plain characters in text.
+/// By default, glyphs are rendered as HTML character references.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Glyphs {
+    /// Replaces `'` inside word, e.g. `don't` or `'90s`.
+    pub apostrophe: String,
+    /// Replaces `(C)`.
+    pub copyright: String,
+    /// Replaces `x` between numbers, e.g. `2 x 3`.
+    pub dimension: String,
+    /// Replaces `...`.
+    pub ellipsis: String,
+    /// Replaces `--`.
+    pub em_dash: String,
+    /// Replaces ` - `.
+    pub en_dash: String,
+    /// Inserted before `:`, `;`, `!` and `?` at the end of word, e.g. non-breaking space in French.
+    pub punctuation_space: String,
+    /// Replaces closing `"`.
+    pub quote_double_close: String,
+    /// Replaces opening `"`.
+    pub quote_double_open: String,
+    /// Replaces closing `'`.
+    pub quote_single_close: String,
+    /// Replaces opening `'`.
+    pub quote_single_open: String,
+    /// Replaces `(R)`.
+    pub registered: String,
+    /// Replaces `(TM)`.
+    pub trademark: String,
+}
+
+impl Default for Glyphs {
+    fn default() -> Glyphs {
+        Glyphs {
+            apostrophe: "&#8217;".to_string(),
+            copyright: "&#169;".to_string(),
+            dimension: "&#215;".to_string(),
+            ellipsis: "&#8230;".to_string(),
+            em_dash: "&#8212;".to_string(),
+            en_dash: "&#8211;".to_string(),
+            punctuation_space: String::new(),
+            quote_double_close: "&#8221;".to_string(),
+            quote_double_open: "&#8220;".to_string(),
+            quote_single_close: "&#8217;".to_string(),
+            quote_single_open: "&#8216;".to_string(),
+            registered: "&#174;".to_string(),
+            trademark: "&#8482;".to_string(),
+        }
+    }
+}
+
+impl Glyphs {
+    /// Returns glyphs for language code, e.g. `de` or `fr-CA`. Supported languages are
+    /// English, German, French and Russian. Other languages get English glyphs.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let glyphs = textile::Glyphs::for_lang("de-AT");
+    /// assert_eq!(glyphs.quote_double_open, "&#8222;".to_string());
+    /// ```
+    pub fn for_lang(lang: &str) -> Glyphs {
+        let lang = lang.split('-').next().unwrap_or("").to_lowercase();
+
+        match &*lang {
+            "de" => Glyphs {
+                quote_double_close: "&#8220;".to_string(),
+                quote_double_open: "&#8222;".to_string(),
+                quote_single_close: "&#8216;".to_string(),
+                quote_single_open: "&#8218;".to_string(),
+                ..Glyphs::default()
+            },
+            "fr" => Glyphs {
+                punctuation_space: "&#160;".to_string(),
+                quote_double_close: "&#160;&#187;".to_string(),
+                quote_double_open: "&#171;&#160;".to_string(),
+                quote_single_close: "&#160;&#8250;".to_string(),
+                quote_single_open: "&#8249;&#160;".to_string(),
+                ..Glyphs::default()
+            },
+            "ru" => Glyphs {
+                quote_double_close: "&#187;".to_string(),
+                quote_double_open: "&#171;".to_string(),
+                quote_single_close: "&#8220;".to_string(),
+                quote_single_open: "&#8222;".to_string(),
+                ..Glyphs::default()
+            },
+            _ => Glyphs::default(),
+        }
+    }
+}
+
+/// Checks that glyphs are default or selected for language, so they aren't customized.
+pub fn is_builtin(glyphs: &Glyphs) -> bool {
+    *glyphs == Glyphs::default() || ["de", "fr", "ru"].iter().any(|lang| *glyphs == Glyphs::for_lang(lang))
+}
+
+/// Replaces plain characters in text with typographic glyphs.
+/// `prev` is the character before text, which is used to detect closing quotes.
+pub fn replace_glyphs(text: &str, prev: Option<char>, glyphs: &Glyphs) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut res = String::with_capacity(text.len());
+    let mut idx = 0;
+
+    while idx < chars.len() {
+        let ch = chars[idx];
+        let before = if idx > 0 { Some(chars[idx - 1]) } else { prev };
+        let after = chars.get(idx + 1).cloned();
+
+        match ch {
+            '\'' => {
+                let is_inside_word = before.is_some_and(char::is_alphanumeric) &&
+                                     after.is_some_and(char::is_alphanumeric);
+                let is_year = before.is_none_or(char::is_whitespace) &&
+                              after.is_some_and(|ch| ch.is_ascii_digit());
+
+                res.push_str(if is_inside_word || is_year {
+                    &glyphs.apostrophe
+                } else if is_closing_quote(before, after) {
+                    &glyphs.quote_single_close
+                } else {
+                    &glyphs.quote_single_open
+                });
+            }
+            '"' => {
+                res.push_str(if is_closing_quote(before, after) {
+                    &glyphs.quote_double_close
+                } else {
+                    &glyphs.quote_double_open
+                });
+            }
+            '.' if chars[idx..].starts_with(&['.', '.', '.']) => {
+                res.push_str(&glyphs.ellipsis);
+                idx += 3;
+                continue;
+            }
+            '-' if after == Some('-') => {
+                res.push_str(&glyphs.em_dash);
+                idx += 2;
+                continue;
+            }
+            '-' if before == Some(' ') && after == Some(' ') => res.push_str(&glyphs.en_dash),
+            'x' if is_dimension(&chars, idx) => res.push_str(&glyphs.dimension),
+            ':' | ';' | '!' | '?' if !glyphs.punctuation_space.is_empty() &&
+                                    before.is_some_and(|ch| ch != '\n') &&
+                                    after.is_none_or(char::is_whitespace) => {
+                // Replaces plain space before punctuation, e.g. `Bonjour !`.
+                if before == Some(' ') {
+                    res.pop();
+                }
+                res.push_str(&glyphs.punctuation_space);
+                res.push(ch);
+            }
+            '(' | '[' => {
+                let symbol = [("TM", &glyphs.trademark), ("R", &glyphs.registered), ("C", &glyphs.copyright)]
+                    .iter()
+                    .find(|&&(name, _)| is_symbol(&chars[idx + 1..], name, ch))
+                    .map(|&(name, glyph)| (name.len(), glyph));
+
+                match symbol {
+                    Some((len, glyph)) => {
+                        // Removes space between word and symbol, e.g. `Textile (TM)`.
+                        if before == Some(' ') && idx > 1 && chars[idx - 2].is_alphanumeric() {
+                            res.pop();
+                        }
+                        res.push_str(glyph);
+                        idx += len + 2;
+                        continue;
+                    }
+                    None => res.push(ch),
+                }
+            }
+            _ => res.push(ch),
+        }
+        idx += 1;
+    }
+    res
+}
+
+fn is_closing_quote(before: Option<char>, after: Option<char>) -> bool {
+    before.is_some_and(|ch| !ch.is_whitespace()) &&
+    after.is_none_or(|ch| ch.is_whitespace() || ch.is_ascii_punctuation())
+}
+
+/// Checks that `x` at specified position is placed between numbers, e.g. `2x3` or `2 x 3`.
+fn is_dimension(chars: &[char], idx: usize) -> bool {
+    let is_digit_at = |idx: usize| chars.get(idx).is_some_and(|ch| ch.is_ascii_digit());
+    let number_before = idx > 0 && (is_digit_at(idx - 1) ||
+                                    chars[idx - 1] == ' ' && idx > 1 && is_digit_at(idx - 2));
+    let number_after = is_digit_at(idx + 1) || chars.get(idx + 1) == Some(&' ') && is_digit_at(idx + 2);
+
+    number_before && number_after
+}
+
+/// Checks that text starts with symbol name in any case and closing bracket, e.g. `tm)` or `R]`.
+fn is_symbol(chars: &[char], name: &str, bracket: char) -> bool {
+    let closing_bracket = if bracket == '(' { ')' } else { ']' };
+
+    chars.len() > name.len() &&
+    chars.iter().zip(name.chars()).all(|(ch, name_ch)| ch.to_ascii_uppercase() == name_ch) &&
+    chars[name.len()] == closing_bracket
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replace(text: &str) -> String {
+        replace_glyphs(text, None, &Glyphs::default())
+    }
+
+    #[test]
+    fn replaces_quotes_correctly() {
+        assert_eq!(replace("\"Quote\" and 'single quote'."),
+                   "&#8220;Quote&#8221; and &#8216;single quote&#8217;.");
+        assert_eq!(replace("Don't touch the '90s dogs' bones"),
+                   "Don&#8217;t touch the &#8217;90s dogs&#8217; bones");
+        assert_eq!(replace_glyphs("\" after bold", Some('a'), &Glyphs::default()),
+                   "&#8221; after bold");
+    }
+
+    #[test]
+    fn replaces_dashes_and_ellipsis_correctly() {
+        assert_eq!(replace("Wait... 1990 - 2000 -- now"),
+                   "Wait&#8230; 1990 &#8211; 2000 &#8212; now");
+        assert_eq!(replace("well-known"), "well-known");
+    }
+
+    #[test]
+    fn replaces_dimension_sign_correctly() {
+        assert_eq!(replace("2 x 3 and 4x5, but not x 6 or box"),
+                   "2 &#215; 3 and 4&#215;5, but not x 6 or box");
+    }
+
+    #[test]
+    fn replaces_glyphs_for_lang_correctly() {
+        assert_eq!(replace_glyphs("\"Anführungszeichen\"", None, &Glyphs::for_lang("de")),
+                   "&#8222;Anführungszeichen&#8220;");
+        assert_eq!(replace_glyphs("\"Guillemets\" : oui! Non ?", None, &Glyphs::for_lang("fr-FR")),
+                   "&#171;&#160;Guillemets&#160;&#187;&#160;: oui&#160;! Non&#160;?");
+        assert_eq!(replace_glyphs("\"Кавычки\"", None, &Glyphs::for_lang("ru")),
+                   "&#171;Кавычки&#187;");
+        assert_eq!(replace_glyphs("http://example.com", None, &Glyphs::for_lang("fr")),
+                   "http://example.com");
+    }
+
+    #[test]
+    fn replaces_symbols_correctly() {
+        assert_eq!(replace("Textile (TM), Rust[r] (c) 2017 and (class)"),
+                   "Textile&#8482;, Rust&#174; &#169; 2017 and (class)");
+    }
+}
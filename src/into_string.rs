@@ -1,12 +1,12 @@
 use std::fs::File;
+use std::io::{self, Read};
 use std::path::Path;
-use std::io::Read;
 
 pub trait IntoString {
     fn into_string(&self) -> String;
 }
 
-impl IntoString for &'static str {
+impl<'a> IntoString for &'a str {
     fn into_string(&self) -> String {
         self.to_string()
     }
@@ -18,11 +18,14 @@ impl IntoString for String {
     }
 }
 
-impl<'a> IntoString for &'a Path {
-    fn into_string(&self) -> String {
-        let mut file = File::open(self).unwrap();
-        let mut contents = String::new();
-        file.read_to_string(&mut contents).unwrap();
-        contents
-    }
+/// Reads contents of file. Returns error, if file can't be read or isn't valid UTF-8.
+pub fn read_file<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    read_string(File::open(path)?)
+}
+
+/// Reads all text from reader. Returns error, if reading fails or text isn't valid UTF-8.
+pub fn read_string<R: Read>(mut reader: R) -> io::Result<String> {
+    let mut contents = String::new();
+    reader.read_to_string(&mut contents)?;
+    Ok(contents)
 }
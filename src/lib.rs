@@ -18,7 +18,7 @@
 //! assert_eq!(html, "<h1><strong>Textile markup language</strong></h1>".to_string());
 //! ```
 
-#![recursion_limit="100"]
+#![recursion_limit="200"]
 
 #[cfg(test)]
 #[macro_use]
@@ -26,10 +26,16 @@ extern crate maplit;
 #[macro_use]
 extern crate lazy_static;
 extern crate regex;
+#[cfg(feature = "serde")]
+extern crate serde;
 extern crate url;
 
+mod glyphs;
 mod into_string;
 pub mod parser;
 mod renderer;
+mod writer;
 
+pub use glyphs::Glyphs;
 pub use renderer::*;
+pub use writer::*;
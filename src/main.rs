@@ -1,14 +1,19 @@
 extern crate clap;
+#[cfg(feature = "serde")]
+extern crate serde_json;
 extern crate textile;
 
-use std::fs::File;
-use std::io::Write;
+use std::fs::{self, File};
+use std::io;
 use std::path::Path;
-use clap::{App, AppSettings, Arg};
+use std::process;
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use textile::parser::Block;
 
 fn main() {
     let matches = App::new(env!("CARGO_PKG_NAME"))
                     .setting(AppSettings::ArgRequiredElseHelp)
+                    .setting(AppSettings::SubcommandsNegateReqs)
                     .version(env!("CARGO_PKG_VERSION"))
                     .version_short("v")
                     .about("Renders Textile markup language into HTML")
@@ -22,17 +27,87 @@ fn main() {
                     .arg(Arg::with_name("parse")
                             .long("parse")
                             .help("Prints parse tree"))
+                    .arg(Arg::with_name("json")
+                            .long("json")
+                            .requires("parse")
+                            .help("Prints parse tree as JSON, requires `serde` feature"))
+                    .subcommand(SubCommand::with_name("fmt")
+                                    .about("Formats Textile markup without changing rendered HTML")
+                                    .arg(Arg::with_name("INPUT")
+                                            .help("Input file")
+                                            .required(true)
+                                            .index(1))
+                                    .arg(Arg::with_name("OUTPUT")
+                                            .help("Output file, which may be the input file. By default, formatted markup is printed")
+                                            .index(2))
+                                    .arg(Arg::with_name("width")
+                                            .long("width")
+                                            .short("w")
+                                            .takes_value(true)
+                                            .default_value("80")
+                                            .validator(|width| width.parse::<usize>().map(|_| ()).map_err(|err| err.to_string()))
+                                            .help("Column limit of text lines")))
                     .get_matches();
 
+    if let Some(matches) = matches.subcommand_matches("fmt") {
+        format(matches);
+        return;
+    }
+
     let ref input = matches.value_of("INPUT").unwrap();
     let output = matches.value_of("OUTPUT");
     let parse = matches.is_present("parse");
 
     if let Some(ref output_file) = output {
-        let mut f = File::create(&Path::new(output_file)).expect("Unable to create file");
-        f.write_all(textile::render(Path::new(input)).as_bytes()).expect("Unable to write data");
+        let text = fs::read_to_string(input).unwrap_or_else(|err| exit_with_error("Unable to read file", err));
+        let mut f = File::create(&Path::new(output_file))
+                        .unwrap_or_else(|err| exit_with_error("Unable to create file", err));
+        textile::render_to(&mut f, text, textile::RenderOptions::default())
+            .unwrap_or_else(|err| exit_with_error("Unable to write data", err));
     }
     if parse {
-        println!("{:#?}", textile::parser::parse(Path::new(input)));
+        let blocks = textile::parser::parse_file(Path::new(input))
+                         .unwrap_or_else(|err| exit_with_error("Unable to read file", err));
+
+        if matches.is_present("json") {
+            print_json(&blocks);
+        } else {
+            println!("{:#?}", blocks);
+        }
+    }
+}
+
+fn format(matches: &ArgMatches) {
+    let input = matches.value_of("INPUT").unwrap();
+    // Width is checked by validator of argument.
+    let width = matches.value_of("width").unwrap().parse().unwrap();
+    let text = fs::read_to_string(input).unwrap_or_else(|err| exit_with_error("Unable to read file", err));
+    let formatted = textile::format(text, width);
+
+    match matches.value_of("OUTPUT") {
+        Some(output_file) => {
+            fs::write(output_file, formatted).unwrap_or_else(|err| exit_with_error("Unable to write file", err));
+        }
+        None => print!("{}", formatted),
     }
 }
+
+#[cfg(feature = "serde")]
+fn print_json(blocks: &[Block]) {
+    // Keys of attributes are sorted in JSON value, so the same document is always printed the same way.
+    let json = serde_json::to_value(blocks)
+                   .and_then(|value| serde_json::to_string_pretty(&value))
+                   .unwrap_or_else(|err| exit_with_error("Unable to serialize parse tree", err.into()));
+    println!("{}", json);
+}
+
+#[cfg(not(feature = "serde"))]
+fn print_json(_: &[Block]) {
+    eprintln!("JSON output requires `serde` feature");
+    process::exit(1);
+}
+
+fn exit_with_error(message: &str, err: io::Error) -> ! {
+    eprintln!("{}: {}", message, err);
+    process::exit(1);
+}
@@ -1,4 +1,6 @@
 use super::Attributes;
+use super::diagnostic::Severity;
+use super::location;
 use super::patterns::{
     ATTRS_STR_PATTERN,
     PADDING_PATTERN,
@@ -7,15 +9,22 @@ use super::patterns::{
     CLASS_ID_PATTERN,
     CSS_PROPS_PATTERN,
     CSS_PROPS_SPLIT_PATTERN,
-    CSS_PROP_STR_PATTERN
+    CSS_PROP_STR_PATTERN,
+    COLSPAN_PATTERN,
+    ROWSPAN_PATTERN,
+    VERTICAL_ALIGN_PATTERN
 };
 
 pub fn parse_block_attributes(attrs_str: &str) -> Attributes {
     let (mut attrs, string, mut css_props) = parse_attributes(attrs_str);
+    let unknown: String = string.chars().filter(|ch| !"()<>=\\/^~- ".contains(*ch) && !ch.is_ascii_digit()).collect();
 
-    if PADDING_PATTERN.is_match(&*string) {
-        let caps = PADDING_PATTERN.captures(&*string).unwrap();
-        let padding = caps.at(0).unwrap();
+    if !unknown.is_empty() {
+        location::report(Severity::Error, format!("Malformed attributes `{}` are ignored", attrs_str), attrs_str);
+    }
+
+    if let Some(caps) = PADDING_PATTERN.captures(&*string) {
+        let padding = caps.at(0).unwrap_or("");
         let padding_type = if padding.starts_with("(") {
             "padding-left"
         } else {
@@ -24,9 +33,8 @@ pub fn parse_block_attributes(attrs_str: &str) -> Attributes {
         css_props.push(format!("{}: {}em", padding_type, padding.len()));
     }
 
-    if ALIGN_PATTERN.is_match(&*string) {
-        let caps = ALIGN_PATTERN.captures(&*string).unwrap();
-        let text_align = match caps.at(0).unwrap() {
+    if let Some(caps) = ALIGN_PATTERN.captures(&*string) {
+        let text_align = match caps.at(0).unwrap_or("") {
             ">" => "right",
             "<>" => "justify",
             "=" => "center",
@@ -42,19 +50,48 @@ pub fn parse_block_attributes(attrs_str: &str) -> Attributes {
     attrs
 }
 
-pub fn parse_inline_attributes(text: &str) -> (Attributes, String) {
+pub fn parse_table_attributes(attrs_str: &str) -> Attributes {
+    let (_, string, _) = parse_attributes(attrs_str);
+    let mut attrs = parse_block_attributes(attrs_str);
+
+    if let Some(caps) = COLSPAN_PATTERN.captures(&string) {
+        attrs.insert("colspan".to_string(), caps.at(1).unwrap_or("").to_string());
+    }
+
+    if let Some(caps) = ROWSPAN_PATTERN.captures(&string) {
+        attrs.insert("rowspan".to_string(), caps.at(1).unwrap_or("").to_string());
+    }
+
+    if let Some(caps) = VERTICAL_ALIGN_PATTERN.captures(&string) {
+        let vertical_align = match caps.at(0).unwrap_or("") {
+            "^" => "top",
+            "~" => "bottom",
+            _ => "middle",
+        };
+        let style = match attrs.get("style") {
+            Some(style) => format!("{}; vertical-align: {}", style, vertical_align),
+            None => format!("vertical-align: {}", vertical_align),
+        };
+        attrs.insert("style".to_string(), style);
+    }
+
+    attrs
+}
+
+pub fn parse_inline_attributes(text: &str) -> (Attributes, &str) {
     // Match any brackets at the beginning of string.
-    let attrs_string = ATTRS_STR_PATTERN.captures(text).unwrap().at(0).unwrap();
+    let attrs_string = ATTRS_STR_PATTERN.find(text).map_or("", |(_, end)| &text[..end]);
     let (mut attrs, _, css_props) = parse_attributes(attrs_string);
 
-    if text.starts_with(attrs_string) {
-        if !css_props.is_empty() {
-            attrs.insert("style".to_string(), css_props.join("; "));
-        }
+    match text.strip_prefix(attrs_string) {
+        Some(rest) => {
+            if !css_props.is_empty() {
+                attrs.insert("style".to_string(), css_props.join("; "));
+            }
 
-        (attrs, text.replace(attrs_string, ""))
-    } else {
-        (Attributes::new(), text.to_string())
+            (attrs, rest)
+        }
+        None => (Attributes::new(), text),
     }
 }
 
@@ -63,14 +100,12 @@ fn parse_attributes(attrs_str: &str) -> (Attributes, String, Vec<String>) {
     let mut string = attrs_str.to_string();
     let mut css_props = Vec::new();
 
-    if LANG_PATTERN.is_match(attrs_str) {
-        let lang = LANG_PATTERN.captures(attrs_str).unwrap().at(1).unwrap();
+    if let Some(lang) = LANG_PATTERN.captures(attrs_str).and_then(|caps| caps.at(1)) {
         string = string.replace(lang, "");
         attrs.insert("lang".to_string(), lang.to_string());
     }
 
-    if CLASS_ID_PATTERN.is_match(attrs_str) {
-        let caps = CLASS_ID_PATTERN.captures(attrs_str).unwrap();
+    if let Some(caps) = CLASS_ID_PATTERN.captures(attrs_str) {
         let class = caps.name("class").unwrap_or("").to_string();
         let id = caps.name("id").unwrap_or("").to_string();
 
@@ -81,19 +116,26 @@ fn parse_attributes(attrs_str: &str) -> (Attributes, String, Vec<String>) {
         if !id.is_empty() {
             attrs.insert("id".to_string(), id);
         }
-        string = string.replace(caps.at(0).unwrap(), "");
+        string = string.replace(caps.at(0).unwrap_or(""), "");
     }
 
-    if CSS_PROPS_PATTERN.is_match(attrs_str) {
-        let caps = CSS_PROPS_PATTERN.captures(attrs_str).unwrap();
-
-        for css_prop in CSS_PROPS_SPLIT_PATTERN.split(caps.at(1).unwrap()) {
-            let caps = CSS_PROP_STR_PATTERN.captures(css_prop).unwrap();
-            let key = caps.name("key").unwrap().to_string();
-            let value = caps.name("value").unwrap().to_string();
-            css_props.push(format!("{}: {}", key, value));
+    if let Some(caps) = CSS_PROPS_PATTERN.captures(attrs_str) {
+        for css_prop in CSS_PROPS_SPLIT_PATTERN.split(caps.at(1).unwrap_or("")) {
+            match CSS_PROP_STR_PATTERN.captures(css_prop) {
+                Some(caps) => {
+                    let key = caps.name("key").unwrap_or("").to_string();
+                    let value = caps.name("value").unwrap_or("").to_string();
+                    css_props.push(format!("{}: {}", key, value));
+                }
+                None if css_prop.trim().is_empty() => {}
+                None => {
+                    location::report(Severity::Error,
+                                     format!("CSS property `{}` without value is ignored", css_prop.trim()),
+                                     css_prop);
+                }
+            }
         }
-        string = string.replace(caps.at(0).unwrap(), "");
+        string = string.replace(caps.at(0).unwrap_or(""), "");
     }
 
     (attrs, string, css_props)
@@ -114,6 +156,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn not_parse_css_property_without_value() {
+        assert_eq!(
+            parse_block_attributes("{color; font-size: 1em}"),
+            hashmap!{
+                "style".to_string() => "font-size: 1em".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_table_attributes_correctly() {
+        assert_eq!(
+            parse_table_attributes("\\2/3^(my-class){color: dark-red}>"),
+            hashmap!{
+                "class".to_string() => "my-class".to_string(),
+                "colspan".to_string() => "2".to_string(),
+                "rowspan".to_string() => "3".to_string(),
+                "style".to_string() => "color: dark-red; text-align: right; vertical-align: top".to_string(),
+            }
+        );
+    }
+
     #[test]
     fn parses_inline_attributes_correctly() {
         assert_eq!(
@@ -125,7 +190,7 @@ mod tests {
                     "lang".to_string() => "en".to_string(),
                     "style".to_string() => "font-size: 1em; background-color: #fff".to_string(),
                 },
-                "".to_string(),
+                "",
             )
         );
         assert_eq!(
@@ -135,7 +200,7 @@ mod tests {
                     "class".to_string() => "class-name".to_string(),
                     "style".to_string() => "text-align: center".to_string(),
                 },
-                "".to_string(),
+                "",
             )
         );
     }
@@ -1,26 +1,25 @@
 use parser::Block;
 use parser::attributes::parse_block_attributes;
-use parser::block::parse_block;
+use parser::block::is_block_start;
 use parser::block::paragraph::parse_paragraph;
 use parser::inline::parse_inline_elements;
 use parser::patterns::BLOCK_QUOTATION_PATTERN;
 
-pub fn parse_block_quotation(lines: &[&str]) -> Option<(Block, usize)> {
+pub fn parse_block_quotation<'a>(lines: &[&'a str]) -> Option<(Block<'a>, usize)> {
     let mut cur_line = 1;
 
-    if BLOCK_QUOTATION_PATTERN.is_match(lines[0]) {
-        let caps = BLOCK_QUOTATION_PATTERN.captures(lines[0]).unwrap();
-        let mut bq_attrs = parse_block_attributes(caps.name("attributes").unwrap());
+    if let Some(caps) = BLOCK_QUOTATION_PATTERN.captures(lines[0]) {
+        let mut bq_attrs = parse_block_attributes(caps.name("attributes").unwrap_or(""));
         let p_attrs = bq_attrs.clone();
         let mut blocks = Vec::new();
         let mut strings = Vec::new();
-        strings.push(&lines[0][caps.at(0).unwrap().len()..]);
+        strings.push(&lines[0][caps.at(0).unwrap_or("").len()..]);
 
         if let Some(cite) = caps.name("cite") {
             bq_attrs.insert("cite".to_string(), cite.to_string());
         }
 
-        if caps.name("mode").unwrap().len() == 1 {
+        if caps.name("mode").unwrap_or("").len() == 1 {
             // Breaks parsing if line is empty.
             for line in &lines[1..] {
                 cur_line += 1;
@@ -39,20 +38,9 @@ pub fn parse_block_quotation(lines: &[&str]) -> Option<(Block, usize)> {
             for (idx, line) in (&lines[1..]).iter().enumerate() {
                 cur_line += 1;
 
-                if lines[idx].is_empty() {
-                    match parse_block(&[line]) {
-                        Some((Block::Paragraph { starts_with_p, .. }, _)) => {
-                            if starts_with_p {
-                                cur_line -= 1;
-                                break;
-                            }
-                        }
-                        Some(_) => {
-                            cur_line -= 1;
-                            break;
-                        }
-                        _ => {}
-                    }
+                if lines[idx].is_empty() && is_block_start(line) {
+                    cur_line -= 1;
+                    break;
                 }
                 strings.push(line);
             }
@@ -66,6 +54,8 @@ pub fn parse_block_quotation(lines: &[&str]) -> Option<(Block, usize)> {
                     }
                     line_pos += lines_count;
                     blocks.push(paragraph);
+                } else {
+                    break;
                 }
             }
         }
@@ -101,7 +91,7 @@ mod tests {
                                 Inline::Bold {
                                     attributes: Attributes::new(),
                                     elements: vec![
-                                        Inline::Text("My quote".to_string()),
+                                        Inline::Text("My quote".into()),
                                     ],
                                     tag_type: "strong".to_string(),
                                 },
@@ -126,14 +116,14 @@ mod tests {
                         Block::Paragraph {
                             attributes: Attributes::new(),
                             elements: vec![
-                                Inline::Text("Block quotation".to_string()),
+                                Inline::Text("Block quotation".into()),
                             ],
                             starts_with_p: false,
                         },
                         Block::Paragraph {
                             attributes: Attributes::new(),
                             elements: vec![
-                                Inline::Text("in multiline mode".to_string()),
+                                Inline::Text("in multiline mode".into()),
                             ],
                             starts_with_p: false,
                         },
@@ -151,14 +141,14 @@ mod tests {
                         Block::Paragraph {
                             attributes: Attributes::new(),
                             elements: vec![
-                                Inline::Text("Block quotation".to_string()),
+                                Inline::Text("Block quotation".into()),
                             ],
                             starts_with_p: false,
                         },
                         Block::Paragraph {
                             attributes: Attributes::new(),
                             elements: vec![
-                                Inline::Text("in multiline mode".to_string()),
+                                Inline::Text("in multiline mode".into()),
                             ],
                             starts_with_p: false,
                         },
@@ -182,7 +172,7 @@ mod tests {
                         Block::Paragraph {
                             attributes: Attributes::new(),
                             elements: vec![
-                                Inline::Text("Block quotation".to_string()),
+                                Inline::Text("Block quotation".into()),
                             ],
                             starts_with_p: false,
                         },
@@ -1,17 +1,17 @@
 use parser::Block;
 use parser::attributes::parse_block_attributes;
-use parser::block::parse_block;
+use parser::block::is_block_start;
 use parser::patterns::CODE_BLOCK_PATTERN;
+use std::borrow::Cow;
 
-pub fn parse_code_block(lines: &[&str]) -> Option<(Block, usize)> {
+pub fn parse_code_block<'a>(lines: &[&'a str]) -> Option<(Block<'a>, usize)> {
     let mut cur_line = 1;
 
-    if CODE_BLOCK_PATTERN.is_match(lines[0]) {
-        let caps = CODE_BLOCK_PATTERN.captures(lines[0]).unwrap();
+    if let Some(caps) = CODE_BLOCK_PATTERN.captures(lines[0]) {
         let mut strings = Vec::new();
-        strings.push(&lines[0][caps.at(0).unwrap().len()..]);
+        strings.push(&lines[0][caps.at(0).unwrap_or("").len()..]);
 
-        if caps.name("mode").unwrap().len() == 1 {
+        if caps.name("mode").unwrap_or("").len() == 1 {
             // Breaks parsing if line is empty.
             for line in &lines[1..] {
                 cur_line += 1;
@@ -25,20 +25,9 @@ pub fn parse_code_block(lines: &[&str]) -> Option<(Block, usize)> {
             for (idx, line) in (&lines[1..]).iter().enumerate() {
                 cur_line += 1;
 
-                if lines[idx].is_empty() {
-                    match parse_block(&[line]) {
-                        Some((Block::Paragraph { starts_with_p, .. }, _)) => {
-                            if starts_with_p {
-                                cur_line -= 1;
-                                break;
-                            }
-                        }
-                        Some(_) => {
-                            cur_line -= 1;
-                            break;
-                        }
-                        _ => {}
-                    }
+                if lines[idx].is_empty() && is_block_start(line) {
+                    cur_line -= 1;
+                    break;
                 }
                 strings.push(line);
             }
@@ -46,8 +35,12 @@ pub fn parse_code_block(lines: &[&str]) -> Option<(Block, usize)> {
 
         Some((
             Block::CodeBlock {
-                attributes: parse_block_attributes(caps.name("attributes").unwrap()),
-                code: strings.join("\n").trim_right().to_string(),
+                attributes: parse_block_attributes(caps.name("attributes").unwrap_or("")),
+                code: if strings.len() == 1 {
+                    Cow::Borrowed(strings[0].trim_end())
+                } else {
+                    Cow::Owned(strings.join("\n").trim_end().to_string())
+                },
             },
             cur_line
         ))
@@ -68,7 +61,7 @@ mod tests {
             Some((
                 Block::CodeBlock {
                     attributes: Attributes::new(),
-                    code: "print('Hello World')\nprint(10 * 4)".to_string()
+                    code: "print('Hello World')\nprint(10 * 4)".into()
                 },
                 2
             ))
@@ -90,7 +83,7 @@ mod tests {
             Some((
                 Block::CodeBlock {
                     attributes: Attributes::new(),
-                    code: "#include <iostream>\nusing namespace std\n\nint main() {\n    cout << \"Hello, world!\" << endl;\n    return 0;\n}".to_string(),
+                    code: "#include <iostream>\nusing namespace std\n\nint main() {\n    cout << \"Hello, world!\" << endl;\n    return 0;\n}".into(),
                 },
                 7
             ))
@@ -110,7 +103,7 @@ mod tests {
             Some((
                 Block::CodeBlock {
                     attributes: Attributes::new(),
-                    code: "#include <iostream>\nusing namespace std\n\nint main() {\n    cout << \"Hello, world!\" << endl;\n    return 0;\n}".to_string(),
+                    code: "#include <iostream>\nusing namespace std\n\nint main() {\n    cout << \"Hello, world!\" << endl;\n    return 0;\n}".into(),
                 },
                 8
             ))
@@ -1,16 +1,15 @@
 use parser::Block;
-use parser::block::parse_block;
+use parser::block::is_block_start;
 use parser::patterns::COMMENT_PATTERN;
 
-pub fn parse_comment(lines: &[&str]) -> Option<(Block, usize)> {
+pub fn parse_comment<'a>(lines: &[&'a str]) -> Option<(Block<'a>, usize)> {
     let mut cur_line = 1;
 
-    if COMMENT_PATTERN.is_match(lines[0]) {
-        let caps = COMMENT_PATTERN.captures(lines[0]).unwrap();
+    if let Some(caps) = COMMENT_PATTERN.captures(lines[0]) {
         let mut strings = Vec::new();
-        strings.push((&lines[0]).replace(caps.at(0).unwrap(), ""));
+        strings.push((&lines[0]).replace(caps.at(0).unwrap_or(""), ""));
 
-        if caps.name("mode").unwrap().len() == 1 {
+        if caps.name("mode").unwrap_or("").len() == 1 {
             // Breaks parsing if line is empty.
             for line in &lines[1..] {
                 cur_line += 1;
@@ -24,20 +23,9 @@ pub fn parse_comment(lines: &[&str]) -> Option<(Block, usize)> {
             for (idx, line) in (&lines[1..]).iter().enumerate() {
                 cur_line += 1;
 
-                if lines[idx].is_empty() {
-                    match parse_block(&[line]) {
-                        Some((Block::Paragraph { starts_with_p, .. }, _)) => {
-                            if starts_with_p {
-                                cur_line -= 1;
-                                break;
-                            }
-                        }
-                        Some(_) => {
-                            cur_line -= 1;
-                            break;
-                        }
-                        _ => {}
-                    }
+                if lines[idx].is_empty() && is_block_start(line) {
+                    cur_line -= 1;
+                    break;
                 }
                 strings.push(line.to_string());
             }
@@ -0,0 +1,122 @@
+use parser::Block;
+use parser::attributes::parse_block_attributes;
+use parser::block::{is_block_start, parse_blocks};
+use parser::patterns::DIV_PATTERN;
+
+pub fn parse_div<'a>(lines: &[&'a str]) -> Option<(Block<'a>, usize)> {
+    let mut cur_line = 1;
+
+    if let Some(caps) = DIV_PATTERN.captures(lines[0]) {
+        let mut strings = Vec::new();
+        strings.push(&lines[0][caps.at(0).unwrap_or("").len()..]);
+
+        if caps.name("mode").unwrap_or("").len() == 1 {
+            // Breaks parsing if line is empty.
+            for line in &lines[1..] {
+                cur_line += 1;
+                if line.is_empty() {
+                    break;
+                }
+                strings.push(line);
+            }
+        } else {
+            // Breaks parsing if line is block element.
+            for (idx, line) in lines[1..].iter().enumerate() {
+                cur_line += 1;
+
+                if lines[idx].is_empty() && is_block_start(line) {
+                    cur_line -= 1;
+                    break;
+                }
+                strings.push(line);
+            }
+        }
+
+        Some((
+            Block::Div {
+                attributes: parse_block_attributes(caps.name("attributes").unwrap_or("")),
+                elements: parse_blocks(&strings),
+            },
+            cur_line
+        ))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{Attributes, Block, Inline};
+    use super::*;
+
+    #[test]
+    fn parses_div_correctly() {
+        assert_eq!(
+            parse_div(&["div(note). Some text", "", "Paragraph"]),
+            Some((
+                Block::Div {
+                    attributes: hashmap!{
+                        "class".to_string() => "note".to_string(),
+                    },
+                    elements: vec![
+                        Block::Paragraph {
+                            attributes: Attributes::new(),
+                            elements: vec![
+                                Inline::Text("Some text".into()),
+                            ],
+                            starts_with_p: false,
+                        },
+                    ],
+                },
+                2
+            ))
+        );
+    }
+
+    #[test]
+    fn parses_extended_div_correctly() {
+        assert_eq!(
+            parse_div(&["div.. First", "", "* Item", "", "p. After"]),
+            Some((
+                Block::Div {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        Block::Paragraph {
+                            attributes: Attributes::new(),
+                            elements: vec![
+                                Inline::Text("First".into()),
+                            ],
+                            starts_with_p: false,
+                        },
+                    ],
+                },
+                2
+            ))
+        );
+        assert_eq!(
+            parse_div(&["div.. First", "", "Second", "", "p. After"]),
+            Some((
+                Block::Div {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        Block::Paragraph {
+                            attributes: Attributes::new(),
+                            elements: vec![
+                                Inline::Text("First".into()),
+                            ],
+                            starts_with_p: false,
+                        },
+                        Block::Paragraph {
+                            attributes: Attributes::new(),
+                            elements: vec![
+                                Inline::Text("Second".into()),
+                            ],
+                            starts_with_p: false,
+                        },
+                    ],
+                },
+                4
+            ))
+        );
+    }
+}
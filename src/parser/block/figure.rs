@@ -0,0 +1,101 @@
+use parser::{Block, Inline};
+use parser::attributes::parse_block_attributes;
+use parser::inline::parse_inline_elements;
+use parser::patterns::FIGURE_PATTERN;
+use std::borrow::Cow;
+
+pub fn parse_figure<'a>(lines: &[&'a str]) -> Option<(Block<'a>, usize)> {
+    let mut cur_line = 1;
+
+    if let Some(caps) = FIGURE_PATTERN.captures(lines[0]) {
+        let mut strings = Vec::new();
+        strings.push(lines[0][caps.at(0).unwrap_or("").len()..].trim_start());
+
+        for line in &lines[1..] {
+            cur_line += 1;
+            if line.is_empty() {
+                break;
+            }
+            strings.push(line);
+        }
+
+        let mut elements = parse_inline_elements(&strings).into_iter();
+        let image = match elements.next() {
+            Some(image @ Inline::Image { .. }) => image,
+            _ => return None,
+        };
+        let mut caption: Vec<Inline> = elements.skip_while(|el| *el == Inline::Break).collect();
+
+        if let Some(Inline::Text(ref mut text)) = caption.first_mut() {
+            *text = match *text {
+                Cow::Borrowed(text) => Cow::Borrowed(text.trim_start()),
+                Cow::Owned(ref text) => Cow::Owned(text.trim_start().to_string()),
+            };
+        }
+        if caption.first() == Some(&Inline::Text(Cow::Borrowed(""))) {
+            caption.remove(0);
+        }
+
+        Some((
+            Block::Figure {
+                attributes: parse_block_attributes(caps.name("attributes").unwrap_or("")),
+                caption,
+                image: Box::new(image),
+            },
+            cur_line
+        ))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{Attributes, Block, Inline};
+    use super::*;
+
+    #[test]
+    fn parses_figure_correctly() {
+        assert_eq!(
+            parse_figure(&["fig(screenshot). !image.png(Settings)!", "Settings _window_", ""]),
+            Some((
+                Block::Figure {
+                    attributes: hashmap!{
+                        "class".to_string() => "screenshot".to_string(),
+                    },
+                    caption: vec![
+                        Inline::Text("Settings ".into()),
+                        Inline::Italic {
+                            attributes: Attributes::new(),
+                            elements: vec![
+                                Inline::Text("window".into()),
+                            ],
+                            tag_type: "em".to_string(),
+                        },
+                    ],
+                    image: Box::new(Inline::Image {
+                        attributes: hashmap!{
+                            "alt".to_string() => "Settings".to_string(),
+                            "src".to_string() => "image.png".to_string(),
+                            "title".to_string() => "Settings".to_string(),
+                        },
+                        href: "".to_string(),
+                    }),
+                },
+                3
+            ))
+        );
+    }
+
+    #[test]
+    fn not_parse_figure_without_image() {
+        assert_eq!(parse_figure(&["fig. Caption only"]), None);
+    }
+
+    #[test]
+    fn not_parse_word_starting_with_fig() {
+        assert_eq!(parse_figure(&["fight. !img.png! now"]), None);
+        assert_eq!(parse_figure(&["figure(cls). !img.png!"]), None);
+        assert!(parse_figure(&["fig(cls)[en]{color: red}<. !img.png!"]).is_some());
+    }
+}
@@ -0,0 +1,77 @@
+use parser::Block;
+use parser::attributes::parse_block_attributes;
+use parser::inline::parse_inline_elements;
+use parser::patterns::FOOTNOTE_PATTERN;
+
+pub fn parse_footnote<'a>(lines: &[&'a str]) -> Option<(Block<'a>, usize)> {
+    let mut cur_line = 1;
+
+    if let Some(caps) = FOOTNOTE_PATTERN.captures(lines[0]) {
+        let mut strings = Vec::new();
+        strings.push(&lines[0][caps.at(0).unwrap_or("").len()..]);
+
+        for line in &lines[1..] {
+            cur_line += 1;
+            if line.is_empty() {
+                break;
+            }
+            strings.push(line);
+        }
+
+        Some((
+            Block::Footnote {
+                attributes: parse_block_attributes(caps.name("attributes").unwrap_or("")),
+                backlink: caps.name("backlink").is_some(),
+                elements: parse_inline_elements(&strings),
+                id: caps.name("id").unwrap_or("").to_string(),
+            },
+            cur_line
+        ))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{Attributes, Block, Inline};
+    use super::*;
+
+    #[test]
+    fn parses_footnote_correctly() {
+        assert_eq!(
+            parse_footnote(&["fn1. Footnote text", "", "p. Paragraph"]),
+            Some((
+                Block::Footnote {
+                    attributes: Attributes::new(),
+                    backlink: false,
+                    elements: vec![
+                        Inline::Text("Footnote text".into()),
+                    ],
+                    id: "1".to_string(),
+                },
+                2
+            ))
+        );
+    }
+
+    #[test]
+    fn parses_footnote_with_backlink_correctly() {
+        assert_eq!(
+            parse_footnote(&["fn12^(note). Footnote text"]),
+            Some((
+                Block::Footnote {
+                    attributes: hashmap!{
+                        "class".to_string() => "note".to_string(),
+                    },
+                    backlink: true,
+                    elements: vec![
+                        Inline::Text("Footnote text".into()),
+                    ],
+                    id: "12".to_string(),
+                },
+                1
+            ))
+        );
+    }
+}
@@ -3,14 +3,13 @@ use parser::attributes::parse_block_attributes;
 use parser::inline::parse_inline_elements;
 use parser::patterns::HEADING_PATTERN;
 
-pub fn parse_heading(lines: &[&str]) -> Option<(Block, usize)> {
+pub fn parse_heading<'a>(lines: &[&'a str]) -> Option<(Block<'a>, usize)> {
     let mut cur_line = 1;
 
-    if HEADING_PATTERN.is_match(lines[0]) {
-        let caps = HEADING_PATTERN.captures(lines[0]).unwrap();
-        let level: u8 = caps.name("level").unwrap().parse().unwrap();
+    if let Some(caps) = HEADING_PATTERN.captures(lines[0]) {
+        let level = caps.name("level").and_then(|level| level.parse().ok()).unwrap_or(1);
         let mut strings = Vec::new();
-        strings.push(&lines[0][caps.at(0).unwrap().len()..]);
+        strings.push(&lines[0][caps.at(0).unwrap_or("").len()..]);
 
         for line in &lines[1..] {
             cur_line += 1;
@@ -22,7 +21,7 @@ pub fn parse_heading(lines: &[&str]) -> Option<(Block, usize)> {
 
         Some((
             Block::Heading {
-                attributes: parse_block_attributes(caps.name("attributes").unwrap()),
+                attributes: parse_block_attributes(caps.name("attributes").unwrap_or("")),
                 level: level,
                 elements: parse_inline_elements(&strings),
             },
@@ -50,15 +49,15 @@ mod tests {
                         Inline::Bold {
                             attributes: Attributes::new(),
                             elements: vec![
-                                Inline::Text("Bold text".to_string()),
+                                Inline::Text("Bold text".into()),
                             ],
                             tag_type: "strong".to_string(),
                         },
-                        Inline::Text(" ".to_string()),
+                        Inline::Text(" ".into()),
                         Inline::Italic {
                             attributes: Attributes::new(),
                             elements: vec![
-                                Inline::Text("Italic text".to_string()),
+                                Inline::Text("Italic text".into()),
                             ],
                             tag_type: "em".to_string(),
                         },
@@ -0,0 +1,95 @@
+use parser::Block;
+use parser::patterns::HTML_BLOCK_PATTERN;
+
+pub fn parse_html<'a>(lines: &[&'a str]) -> Option<(Block<'a>, usize)> {
+    if let Some(caps) = HTML_BLOCK_PATTERN.captures(lines[0]) {
+        let tag = caps.name("tag").unwrap_or("").to_lowercase();
+        let mut cur_line = 1;
+
+        if tag != "hr" && caps.name("self_closing").is_none() {
+            // Breaks parsing after the matching closing tag. If there is no closing tag,
+            // breaks parsing if line is empty.
+            let has_closing_tag = lines.iter().any(|line| count_tags(line, &tag).1 > 0);
+            let mut depth = 0;
+
+            for (idx, line) in lines.iter().enumerate() {
+                if !has_closing_tag && line.is_empty() {
+                    break;
+                }
+                let (opening, closing) = count_tags(line, &tag);
+                depth += opening as isize - closing as isize;
+                cur_line = idx + 1;
+
+                if depth <= 0 {
+                    break;
+                }
+            }
+        }
+        let strings = lines[..cur_line].iter().map(|line| line.to_string()).collect();
+
+        // Skip empty line after HTML.
+        if cur_line < lines.len() && lines[cur_line].is_empty() {
+            cur_line += 1;
+        }
+
+        Some((Block::Html(strings), cur_line))
+    } else {
+        None
+    }
+}
+
+/// Returns count of opening and closing tags with specified name in line.
+fn count_tags(line: &str, tag: &str) -> (usize, usize) {
+    let line = line.to_lowercase();
+    let is_tag_end = |rest: &str| {
+        rest.is_empty() || rest.starts_with(|ch: char| ch == '>' || ch == '/' || ch.is_whitespace())
+    };
+    let opening = line.match_indices(&*format!("<{}", tag))
+        .filter(|&(idx, _)| is_tag_end(&line[idx + tag.len() + 1..]))
+        .count();
+    let closing = line.match_indices(&*format!("</{}", tag))
+        .filter(|&(idx, _)| is_tag_end(&line[idx + tag.len() + 2..]))
+        .count();
+
+    (opening, closing)
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::Block;
+    use super::*;
+
+    #[test]
+    fn parses_html_block_correctly() {
+        assert_eq!(
+            parse_html(&["<div class=\"note\">", "<div>*Not bold*</div>", "", "</div>", "", "p. Paragraph"]),
+            Some((
+                Block::Html(vec![
+                    "<div class=\"note\">".to_string(),
+                    "<div>*Not bold*</div>".to_string(),
+                    "".to_string(),
+                    "</div>".to_string(),
+                ]),
+                5
+            ))
+        );
+    }
+
+    #[test]
+    fn parses_unclosed_html_block_correctly() {
+        assert_eq!(
+            parse_html(&["<hr>", "Paragraph"]),
+            Some((Block::Html(vec!["<hr>".to_string()]), 1))
+        );
+        assert_eq!(
+            parse_html(&["<section>", "Text", "", "Paragraph"]),
+            Some((Block::Html(vec!["<section>".to_string(), "Text".to_string()]), 3))
+        );
+    }
+
+    #[test]
+    fn not_parse_inline_html_as_block() {
+        assert_eq!(parse_html(&["<span>Text</span>"]), None);
+        assert_eq!(parse_html(&["<divider>"]), None);
+    }
+}
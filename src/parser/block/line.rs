@@ -0,0 +1,157 @@
+//! Classification of lines by signatures of blocks, e.g. `bc.` of code block, which lines start.
+//!
+//! Line is matched against every signature once, so only parsers of matched blocks are run on it.
+
+use parser::patterns::{
+    BLOCK_QUOTATION_PATTERN,
+    CODE_BLOCK_PATTERN,
+    COMMENT_PATTERN,
+    DEFINITION_LIST_PATTERN,
+    DIV_PATTERN,
+    FIGURE_PATTERN,
+    FOOTNOTE_PATTERN,
+    HEADING_PATTERN,
+    HTML_BLOCK_PATTERN,
+    NO_TEXTILE_BLOCK_PATTERN,
+    NOTE_PATTERN,
+    NOTE_LIST_PATTERN,
+    ORDERED_LIST_PATTERN,
+    PARAGRAPH_PATTERN,
+    PRE_PATTERN,
+    TABLE_PATTERN,
+    TABLE_CAPTION_PATTERN,
+    TABLE_COLGROUP_PATTERN,
+    TABLE_ROW_PATTERN,
+    TABLE_SECTION_PATTERN,
+    UNORDERED_LIST_PATTERN
+};
+
+/// Kind of block other than paragraph, which may start at any line. Kinds are listed in order of priority.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlockKind {
+    BlockQuotation,
+    CodeBlock,
+    Comment,
+    DefinitionList,
+    Div,
+    Figure,
+    Footnote,
+    Heading,
+    Html,
+    List,
+    NoTextile,
+    Note,
+    NoteList,
+    Pre,
+    Table,
+}
+
+impl BlockKind {
+    pub const ALL: [BlockKind; 15] = [
+        BlockKind::BlockQuotation,
+        BlockKind::CodeBlock,
+        BlockKind::Comment,
+        BlockKind::DefinitionList,
+        BlockKind::Div,
+        BlockKind::Figure,
+        BlockKind::Footnote,
+        BlockKind::Heading,
+        BlockKind::Html,
+        BlockKind::List,
+        BlockKind::NoTextile,
+        BlockKind::Note,
+        BlockKind::NoteList,
+        BlockKind::Pre,
+        BlockKind::Table,
+    ];
+
+    /// Checks that line matches signature of block.
+    fn matches(self, line: &str) -> bool {
+        match self {
+            BlockKind::BlockQuotation => BLOCK_QUOTATION_PATTERN.is_match(line),
+            BlockKind::CodeBlock => CODE_BLOCK_PATTERN.is_match(line),
+            BlockKind::Comment => COMMENT_PATTERN.is_match(line),
+            BlockKind::DefinitionList => DEFINITION_LIST_PATTERN.is_match(line),
+            BlockKind::Div => DIV_PATTERN.is_match(line),
+            BlockKind::Figure => FIGURE_PATTERN.is_match(line),
+            BlockKind::Footnote => FOOTNOTE_PATTERN.is_match(line),
+            BlockKind::Heading => HEADING_PATTERN.is_match(line),
+            BlockKind::Html => HTML_BLOCK_PATTERN.is_match(line),
+            BlockKind::List => UNORDERED_LIST_PATTERN.is_match(line) || ORDERED_LIST_PATTERN.is_match(line),
+            BlockKind::NoTextile => NO_TEXTILE_BLOCK_PATTERN.is_match(line),
+            BlockKind::Note => NOTE_PATTERN.is_match(line),
+            BlockKind::NoteList => NOTE_LIST_PATTERN.is_match(line),
+            BlockKind::Pre => PRE_PATTERN.is_match(line),
+            // Table may start with its signature, caption, column group, section or row.
+            BlockKind::Table => {
+                TABLE_PATTERN.is_match(line) ||
+                TABLE_CAPTION_PATTERN.is_match(line) ||
+                TABLE_COLGROUP_PATTERN.is_match(line) ||
+                TABLE_SECTION_PATTERN.is_match(line) ||
+                TABLE_ROW_PATTERN.is_match(line)
+            }
+        }
+    }
+
+    /// Checks that block is parsed whenever its signature is matched.
+    /// Other blocks need more, e.g. figure needs image and table needs rows.
+    pub fn is_certain(self) -> bool {
+        !matches!(self, BlockKind::DefinitionList | BlockKind::Figure | BlockKind::Table)
+    }
+
+    fn bit(self) -> u16 {
+        1 << self as u16
+    }
+}
+
+/// Kinds of blocks, which signatures line matches.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LineKinds {
+    kinds: u16,
+    /// Line starts paragraph with `p.` signature.
+    starts_with_p: bool,
+}
+
+impl LineKinds {
+    pub fn contains(self, kind: BlockKind) -> bool {
+        self.kinds & kind.bit() != 0
+    }
+
+    /// Returns matched kinds in order of priority.
+    pub fn iter(self) -> impl Iterator<Item = BlockKind> {
+        BlockKind::ALL.iter().cloned().filter(move |&kind| self.contains(kind))
+    }
+
+    pub fn starts_with_p(self) -> bool {
+        self.starts_with_p
+    }
+}
+
+/// Matches line against signatures of all blocks.
+pub fn classify(line: &str) -> LineKinds {
+    if line.is_empty() {
+        return LineKinds::default();
+    }
+
+    LineKinds {
+        kinds: BlockKind::ALL.iter()
+            .filter(|kind| kind.matches(line))
+            .fold(0, |kinds, kind| kinds | kind.bit()),
+        starts_with_p: PARAGRAPH_PATTERN.find(line).is_some_and(|(_, end)| end != 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_lines_correctly() {
+        assert_eq!(classify("bc.. code").iter().collect::<Vec<_>>(), vec![BlockKind::CodeBlock]);
+        assert_eq!(classify("###. comment").iter().collect::<Vec<_>>(), vec![BlockKind::Comment, BlockKind::List]);
+        assert_eq!(classify("|a|b|").iter().collect::<Vec<_>>(), vec![BlockKind::Table]);
+        assert_eq!(classify("Text").iter().count(), 0);
+        assert!(classify("p(cls). Text").starts_with_p());
+        assert_eq!(classify(""), LineKinds::default());
+    }
+}
@@ -0,0 +1,193 @@
+use parser::{Block, Inline, LinkAliases};
+use parser::block::is_block_start;
+use parser::patterns::{
+    CODE_BLOCK_PATTERN,
+    COMMENT_PATTERN,
+    LINK_ALIAS_PATTERN,
+    NO_TEXTILE_BLOCK_PATTERN,
+    PRE_PATTERN,
+};
+use parser::visit::visit_inline_elements_mut;
+
+/// Extracts link alias definitions, e.g. `[rust]https://rust-lang.org`, from lines of document.
+/// Definitions are taken only where block may start and not inside extended blocks of code,
+/// preformatted text, comments or raw markup. Lines with definitions are replaced by empty ones,
+/// so they split paragraphs as blank lines do.
+pub fn extract_link_aliases<'a>(lines: &[&'a str]) -> (Vec<&'a str>, LinkAliases) {
+    let mut aliases = LinkAliases::new();
+    let mut res: Vec<&str> = Vec::with_capacity(lines.len());
+    // Extended block, which text is kept as is, continues until empty line followed by block.
+    let mut is_extended = false;
+
+    for (idx, &line) in lines.iter().enumerate() {
+        let is_after_empty = idx == 0 || res[idx - 1].is_empty();
+
+        if is_extended {
+            if !(idx > 0 && lines[idx - 1].is_empty() && is_block_start(line)) {
+                res.push(line);
+                continue;
+            }
+            is_extended = false;
+        }
+
+        match LINK_ALIAS_PATTERN.captures(line) {
+            Some(caps) if is_after_empty => {
+                aliases.insert(caps.name("alias").unwrap_or("").to_string(), caps.name("href").unwrap_or("").to_string());
+                res.push("");
+            }
+            _ => {
+                if is_after_empty {
+                    is_extended = [&*CODE_BLOCK_PATTERN, &*COMMENT_PATTERN, &*NO_TEXTILE_BLOCK_PATTERN, &*PRE_PATTERN]
+                        .iter()
+                        .any(|pattern| pattern.captures(line).is_some_and(|caps| caps.name("mode") == Some("..")));
+                }
+                res.push(line);
+            }
+        }
+    }
+
+    (res, aliases)
+}
+
+/// Replaces targets of links and images, which match defined aliases, with aliased URLs.
+pub fn resolve_link_aliases(blocks: &mut [Block], aliases: &LinkAliases) {
+    if aliases.is_empty() {
+        return;
+    }
+
+    visit_inline_elements_mut(blocks, &mut |element| {
+        let href = match *element {
+            Inline::Link { ref mut attributes, .. } => attributes.get_mut("href"),
+            Inline::Image { ref mut href, .. } => Some(href),
+            _ => None,
+        };
+
+        if let Some(href) = href {
+            if let Some(url) = aliases.get(&*href) {
+                *href = url.clone();
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{Attributes, Block, Inline, LinkAliases};
+    use super::*;
+
+    #[test]
+    fn extracts_link_aliases_correctly() {
+        assert_eq!(
+            extract_link_aliases(&["Some text", "", "[rust]https://rust-lang.org", "[1] is not alias"]),
+            (
+                vec!["Some text", "", "", "[1] is not alias"],
+                hashmap!{
+                    "rust".to_string() => "https://rust-lang.org".to_string(),
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn extracts_link_aliases_with_any_urls_correctly() {
+        assert_eq!(
+            extract_link_aliases(&["[mail]mailto:a@b.c", "[page]page.html", "[files]ftp://example.com/files"]),
+            (
+                vec!["", "", ""],
+                hashmap!{
+                    "mail".to_string() => "mailto:a@b.c".to_string(),
+                    "page".to_string() => "page.html".to_string(),
+                    "files".to_string() => "ftp://example.com/files".to_string(),
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn not_extract_link_aliases_from_text() {
+        let lines = ["bc.. local t = {}", "[k]=1", "", "[k]http://example.com", "return t", "", "p. Text"];
+        assert_eq!(extract_link_aliases(&lines), (lines.to_vec(), LinkAliases::new()));
+
+        let lines = ["pre.. arr", "[i]++", "", "[i]/index"];
+        assert_eq!(extract_link_aliases(&lines), (lines.to_vec(), LinkAliases::new()));
+
+        let lines = ["Checklist:", "[x]done", "[ ]todo"];
+        assert_eq!(extract_link_aliases(&lines), (lines.to_vec(), LinkAliases::new()));
+
+        let lines = ["* One", "[a]http://example.com", "* Two"];
+        assert_eq!(extract_link_aliases(&lines), (lines.to_vec(), LinkAliases::new()));
+    }
+
+    #[test]
+    fn extracts_link_aliases_after_extended_blocks_correctly() {
+        assert_eq!(
+            extract_link_aliases(&["###.. Comment", "", "[a]/comment", "", "p. Text", "", "[b]/page", "[c]https://example.com"]),
+            (
+                vec!["###.. Comment", "", "[a]/comment", "", "p. Text", "", "", ""],
+                hashmap!{
+                    "b".to_string() => "/page".to_string(),
+                    "c".to_string() => "https://example.com".to_string(),
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn resolves_link_aliases_correctly() {
+        let mut blocks = vec![
+            Block::Paragraph {
+                attributes: Attributes::new(),
+                elements: vec![
+                    Inline::Link {
+                        attributes: hashmap!{
+                            "href".to_string() => "rust".to_string(),
+                        },
+                        elements: vec![
+                            Inline::Text("Rust".into()),
+                        ],
+                    },
+                    Inline::Link {
+                        attributes: hashmap!{
+                            "href".to_string() => "http://example.com".to_string(),
+                        },
+                        elements: vec![
+                            Inline::Text("Example".into()),
+                        ],
+                    },
+                ],
+                starts_with_p: false,
+            },
+        ];
+        resolve_link_aliases(&mut blocks, &hashmap!{
+            "rust".to_string() => "https://rust-lang.org".to_string(),
+        });
+
+        assert_eq!(
+            blocks,
+            vec![
+                Block::Paragraph {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        Inline::Link {
+                            attributes: hashmap!{
+                                "href".to_string() => "https://rust-lang.org".to_string(),
+                            },
+                            elements: vec![
+                                Inline::Text("Rust".into()),
+                            ],
+                        },
+                        Inline::Link {
+                            attributes: hashmap!{
+                                "href".to_string() => "http://example.com".to_string(),
+                            },
+                            elements: vec![
+                                Inline::Text("Example".into()),
+                            ],
+                        },
+                    ],
+                    starts_with_p: false,
+                },
+            ]
+        );
+    }
+}
@@ -1,10 +1,11 @@
-use parser::{Attributes, Block, ListElement};
+use parser::{Attributes, Block, DefinitionListElement, ListElement};
 use parser::attributes::parse_inline_attributes;
 use parser::inline::parse_inline_elements;
-use parser::patterns::{ORDERED_LIST_PATTERN, UNORDERED_LIST_PATTERN};
+use parser::location;
+use parser::patterns::{DEFINITION_LIST_PATTERN, ORDERED_LIST_PATTERN, UNORDERED_LIST_PATTERN};
 use regex::Captures;
 
-pub fn parse_list(lines: &[&str]) -> Option<(Block, usize)> {
+pub fn parse_list<'a>(lines: &[&'a str]) -> Option<(Block<'a>, usize)> {
     let empty_line_pos = lines.iter().position(|el| el.is_empty());
     let lines = match empty_line_pos {
         Some(value) => &lines[..value],
@@ -18,14 +19,18 @@ pub fn parse_list(lines: &[&str]) -> Option<(Block, usize)> {
     }
 }
 
-pub fn parse_multilevel_list(lines: &[&str], list_level: usize) -> Option<(Block, usize)> {
+pub fn parse_multilevel_list<'a>(lines: &[&'a str], list_level: usize) -> Option<(Block<'a>, usize)> {
     lazy_static! {
-        static ref FNS: Vec<fn(&[&str], usize) -> Option<(Block, usize)>> = vec![
+        static ref FNS: Vec<for<'b> fn(&[&'b str], usize) -> Option<(Block<'b>, usize)>> = vec![
             parse_ordered_list,
             parse_unordered_list,
         ];
     }
 
+    if lines.is_empty() {
+        return None;
+    }
+
     for f in FNS.iter() {
         let res = f(lines, list_level);
 
@@ -36,15 +41,14 @@ pub fn parse_multilevel_list(lines: &[&str], list_level: usize) -> Option<(Block
     None
 }
 
-fn parse_ordered_list(lines: &[&str], list_level: usize) -> Option<(Block, usize)> {
+fn parse_ordered_list<'a>(lines: &[&'a str], list_level: usize) -> Option<(Block<'a>, usize)> {
     if ORDERED_LIST_PATTERN.is_match(lines[0]) {
         let mut attrs = Attributes::new();
         let mut line_idx = 0;
         let mut elements = Vec::new();
 
-        while line_idx < lines.len() {
-            let (caps, list_type) = get_list_data(lines[line_idx]);
-            let level = caps.name("level").unwrap().len() - 1;
+        while let Some((caps, list_type)) = lines.get(line_idx).and_then(|line| get_list_data(line)) {
+            let level = caps.name("level").map_or(0, |level| level.len() - 1);
 
             if line_idx == 0 {
                 if let Some(start) = caps.name("start") {
@@ -53,8 +57,10 @@ fn parse_ordered_list(lines: &[&str], list_level: usize) -> Option<(Block, usize
             }
 
             if level == list_level && list_type == 'o' {
-                let (attrs, _) = parse_inline_attributes(caps.name("attributes").unwrap());
-                let mut tmp = vec![&lines[line_idx][caps.at(0).unwrap().len()..]];
+                let mark = location::mark();
+                let (attrs, _) = parse_inline_attributes(caps.name("attributes").unwrap_or(""));
+                let start = line_idx;
+                let mut tmp = vec![&lines[line_idx][caps.at(0).unwrap_or("").len()..]];
                 line_idx += 1;
 
                 while line_idx < lines.len() {
@@ -70,10 +76,18 @@ fn parse_ordered_list(lines: &[&str], list_level: usize) -> Option<(Block, usize
                     attributes: attrs,
                     elements: parse_inline_elements(&tmp),
                 });
+                location::push_lines(mark, &lines[start..line_idx]);
             } else if level > list_level {
-                let (list, consumed_lines) = parse_multilevel_list(&lines[line_idx..], level).unwrap();
-                elements.push(ListElement::List(list));
-                line_idx += consumed_lines;
+                let mark = location::mark();
+
+                match parse_multilevel_list(&lines[line_idx..], level) {
+                    Some((list, consumed_lines)) => {
+                        elements.push(ListElement::List(list));
+                        location::push_lines(mark, &lines[line_idx..line_idx + consumed_lines]);
+                        line_idx += consumed_lines;
+                    }
+                    None => break,
+                }
             } else {
                 break;
             }
@@ -92,18 +106,19 @@ fn parse_ordered_list(lines: &[&str], list_level: usize) -> Option<(Block, usize
     }
 }
 
-fn parse_unordered_list(lines: &[&str], list_level: usize) -> Option<(Block, usize)> {
+fn parse_unordered_list<'a>(lines: &[&'a str], list_level: usize) -> Option<(Block<'a>, usize)> {
     if UNORDERED_LIST_PATTERN.is_match(lines[0]) {
         let mut line_idx = 0;
         let mut elements = Vec::new();
 
-        while line_idx < lines.len() {
-            let (caps, list_type) = get_list_data(lines[line_idx]);
-            let level = caps.name("level").unwrap().len() - 1;
+        while let Some((caps, list_type)) = lines.get(line_idx).and_then(|line| get_list_data(line)) {
+            let level = caps.name("level").map_or(0, |level| level.len() - 1);
 
             if level == list_level && list_type == 'u' {
-                let (attrs, _) = parse_inline_attributes(caps.name("attributes").unwrap());
-                let mut tmp = vec![&lines[line_idx][caps.at(0).unwrap().len()..]];
+                let mark = location::mark();
+                let (attrs, _) = parse_inline_attributes(caps.name("attributes").unwrap_or(""));
+                let start = line_idx;
+                let mut tmp = vec![&lines[line_idx][caps.at(0).unwrap_or("").len()..]];
                 line_idx += 1;
 
                 while line_idx < lines.len() {
@@ -119,10 +134,18 @@ fn parse_unordered_list(lines: &[&str], list_level: usize) -> Option<(Block, usi
                     attributes: attrs,
                     elements: parse_inline_elements(&tmp),
                 });
+                location::push_lines(mark, &lines[start..line_idx]);
             } else if level > list_level {
-                let (list, consumed_lines) = parse_multilevel_list(&lines[line_idx..], level).unwrap();
-                elements.push(ListElement::List(list));
-                line_idx += consumed_lines;
+                let mark = location::mark();
+
+                match parse_multilevel_list(&lines[line_idx..], level) {
+                    Some((list, consumed_lines)) => {
+                        elements.push(ListElement::List(list));
+                        location::push_lines(mark, &lines[line_idx..line_idx + consumed_lines]);
+                        line_idx += consumed_lines;
+                    }
+                    None => break,
+                }
             } else {
                 break;
             }
@@ -141,14 +164,330 @@ fn parse_unordered_list(lines: &[&str], list_level: usize) -> Option<(Block, usi
     }
 }
 
+pub fn parse_definition_list<'a>(lines: &[&'a str]) -> Option<(Block<'a>, usize)> {
+    if let Some(caps) = DEFINITION_LIST_PATTERN.captures(lines[0]) {
+        let res = match caps.name("type").unwrap_or("") {
+            "-" => parse_dash_definition_list(lines),
+            ";" => parse_semicolon_definition_list(lines),
+            _ => None,
+        };
+
+        match res {
+            Some((elements, mut line_idx)) => {
+                let has_definitions = elements.iter()
+                    .any(|el| matches!(*el, DefinitionListElement::Definition { .. }));
+
+                if !has_definitions {
+                    return None;
+                }
+                // Skip empty line after list.
+                if line_idx < lines.len() && lines[line_idx].is_empty() {
+                    line_idx += 1;
+                }
+
+                Some((
+                    Block::DefinitionList {
+                        attributes: Attributes::new(),
+                        elements,
+                    },
+                    line_idx
+                ))
+            }
+            None => None,
+        }
+    } else {
+        None
+    }
+}
+
+/// Parses definition list in `- Term := Definition` form.
+/// Definition ending with `=:` may span several lines if nothing follows `:=`.
+fn parse_dash_definition_list<'a>(lines: &[&'a str]) -> Option<(Vec<DefinitionListElement<'a>>, usize)> {
+    let mut elements = Vec::new();
+    let mut line_idx = 0;
+
+    while line_idx < lines.len() {
+        let (attrs, item) = match get_definition_list_data(lines[line_idx]) {
+            Some(('-', attrs, item)) => (attrs, item),
+            _ => break,
+        };
+        line_idx += 1;
+
+        let mark = location::mark();
+
+        match item.find(":=") {
+            Some(pos) => {
+                elements.push(DefinitionListElement::Term {
+                    attributes: attrs.clone(),
+                    elements: parse_inline_elements(&[item[..pos].trim()]),
+                });
+                location::push(mark, location::locate_range(lines[line_idx - 1], item[..pos].trim_end()));
+
+                let mark = location::mark();
+
+                let mut line = item[pos + 2..].trim();
+                // Multi-line definition may contain empty lines until closing `=:`.
+                let end = if line.is_empty() {
+                    lines[line_idx..].iter()
+                        .take_while(|line| get_definition_list_data(line).is_none())
+                        .position(|line| line.trim_end().ends_with("=:"))
+                        .map(|pos| line_idx + pos)
+                } else {
+                    None
+                };
+                let mut strings = Vec::new();
+
+                loop {
+                    let closed = line.trim_end().ends_with("=:");
+                    let text = line.trim_end().trim_end_matches("=:").trim_end();
+
+                    if !text.is_empty() || !strings.is_empty() {
+                        strings.push(text);
+                    }
+                    if closed || line_idx >= lines.len() {
+                        break;
+                    }
+
+                    let next = lines[line_idx];
+                    let is_inside = end.is_some_and(|end| line_idx <= end);
+
+                    if !is_inside && (next.is_empty() || get_definition_list_data(next).is_some()) {
+                        break;
+                    }
+                    line = next;
+                    line_idx += 1;
+                }
+
+                elements.push(DefinitionListElement::Definition {
+                    attributes: attrs,
+                    elements: parse_inline_elements(&strings),
+                });
+                // Definition spans from `:=` to the end of its text.
+                location::push(mark, location::locate_range(&item[pos..], strings.last().unwrap_or(&&item[pos..pos + 2])));
+            }
+            None => {
+                elements.push(DefinitionListElement::Term {
+                    attributes: attrs,
+                    elements: parse_inline_elements(&[item.trim()]),
+                });
+                location::push(mark, location::locate(lines[line_idx - 1]));
+            }
+        }
+    }
+    Some((elements, line_idx))
+}
+
+/// Parses definition list in `; Term` and `: Definition` form.
+fn parse_semicolon_definition_list<'a>(lines: &[&'a str]) -> Option<(Vec<DefinitionListElement<'a>>, usize)> {
+    // Items with index of their first line.
+    let mut items: Vec<(char, Attributes, Vec<&str>, usize)> = Vec::new();
+    let mut line_idx = 0;
+
+    while line_idx < lines.len() && !lines[line_idx].is_empty() {
+        match get_definition_list_data(lines[line_idx]) {
+            Some((item_type, attrs, item)) if item_type != '-' => {
+                items.push((item_type, attrs, vec![item], line_idx));
+            }
+            _ => {
+                match items.last_mut() {
+                    Some(&mut (_, _, ref mut strings, _)) => strings.push(lines[line_idx]),
+                    None => break,
+                }
+            }
+        }
+        line_idx += 1;
+    }
+
+    let elements = items.into_iter()
+        .map(|(item_type, attrs, strings, start)| {
+            let mark = location::mark();
+            let element = if item_type == ';' {
+                DefinitionListElement::Term {
+                    attributes: attrs,
+                    elements: parse_inline_elements(&strings),
+                }
+            } else {
+                DefinitionListElement::Definition {
+                    attributes: attrs,
+                    elements: parse_inline_elements(&strings),
+                }
+            };
+            location::push_lines(mark, &lines[start..start + strings.len()]);
+            element
+        })
+        .collect();
+    Some((elements, line_idx))
+}
+
+fn get_definition_list_data(line: &str) -> Option<(char, Attributes, &str)> {
+    DEFINITION_LIST_PATTERN.captures(line).map(|caps| {
+        let (attrs, _) = parse_inline_attributes(caps.name("attributes").unwrap_or(""));
+        let item_type = caps.name("type").and_then(|item_type| item_type.chars().next()).unwrap_or('-');
+
+        (item_type, attrs, &line[caps.at(0).unwrap_or("").len()..])
+    })
+}
+
 fn is_list_item(line: &str) -> bool {
     UNORDERED_LIST_PATTERN.is_match(line) || ORDERED_LIST_PATTERN.is_match(line)
 }
 
-fn get_list_data(line: &str) -> (Captures, char) {
-    if UNORDERED_LIST_PATTERN.is_match(line) {
-        (UNORDERED_LIST_PATTERN.captures(line).unwrap(), 'u')
-    } else {
-        (ORDERED_LIST_PATTERN.captures(line).unwrap(), 'o')
+fn get_list_data(line: &str) -> Option<(Captures<'_>, char)> {
+    UNORDERED_LIST_PATTERN.captures(line).map(|caps| (caps, 'u'))
+        .or_else(|| ORDERED_LIST_PATTERN.captures(line).map(|caps| (caps, 'o')))
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{Attributes, Block, DefinitionListElement, Inline};
+    use super::*;
+
+    #[test]
+    fn parses_definition_list_correctly() {
+        assert_eq!(
+            parse_definition_list(&["- coffee := Hot and *black*", "- tea", "- mate := Also hot"]),
+            Some((
+                Block::DefinitionList {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        DefinitionListElement::Term {
+                            attributes: Attributes::new(),
+                            elements: vec![
+                                Inline::Text("coffee".into()),
+                            ],
+                        },
+                        DefinitionListElement::Definition {
+                            attributes: Attributes::new(),
+                            elements: vec![
+                                Inline::Text("Hot and ".into()),
+                                Inline::Bold {
+                                    attributes: Attributes::new(),
+                                    elements: vec![
+                                        Inline::Text("black".into()),
+                                    ],
+                                    tag_type: "strong".to_string(),
+                                },
+                            ],
+                        },
+                        DefinitionListElement::Term {
+                            attributes: Attributes::new(),
+                            elements: vec![
+                                Inline::Text("tea".into()),
+                            ],
+                        },
+                        DefinitionListElement::Term {
+                            attributes: Attributes::new(),
+                            elements: vec![
+                                Inline::Text("mate".into()),
+                            ],
+                        },
+                        DefinitionListElement::Definition {
+                            attributes: Attributes::new(),
+                            elements: vec![
+                                Inline::Text("Also hot".into()),
+                            ],
+                        },
+                    ],
+                },
+                3
+            ))
+        );
+    }
+
+    #[test]
+    fn parses_multiline_definition_correctly() {
+        assert_eq!(
+            parse_definition_list(&["- milk :=", "Nourishing beverage", "", "for baby cows. =:", "", "p. Paragraph"]),
+            Some((
+                Block::DefinitionList {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        DefinitionListElement::Term {
+                            attributes: Attributes::new(),
+                            elements: vec![
+                                Inline::Text("milk".into()),
+                            ],
+                        },
+                        DefinitionListElement::Definition {
+                            attributes: Attributes::new(),
+                            elements: vec![
+                                Inline::Text("Nourishing beverage".into()),
+                                Inline::Break,
+                                Inline::Break,
+                                Inline::Text("for baby cows.".into()),
+                            ],
+                        },
+                    ],
+                },
+                5
+            ))
+        );
+    }
+
+    #[test]
+    fn parses_semicolon_definition_list_correctly() {
+        assert_eq!(
+            parse_definition_list(&[";(term) Textile", ": Markup language", ": Fabric"]),
+            Some((
+                Block::DefinitionList {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        DefinitionListElement::Term {
+                            attributes: hashmap!{
+                                "class".to_string() => "term".to_string(),
+                            },
+                            elements: vec![
+                                Inline::Text("Textile".into()),
+                            ],
+                        },
+                        DefinitionListElement::Definition {
+                            attributes: Attributes::new(),
+                            elements: vec![
+                                Inline::Text("Markup language".into()),
+                            ],
+                        },
+                        DefinitionListElement::Definition {
+                            attributes: Attributes::new(),
+                            elements: vec![
+                                Inline::Text("Fabric".into()),
+                            ],
+                        },
+                    ],
+                },
+                3
+            ))
+        );
+    }
+
+    #[test]
+    fn not_parse_definition_list_without_definitions() {
+        assert_eq!(parse_definition_list(&["- Not a term"]), None);
+    }
+
+    #[test]
+    fn records_spans_of_list_items() {
+        let text = "* a\n** b\n* c";
+        let lines: Vec<&str> = text.lines().collect();
+        let (_, spans, _) = location::record(text, || parse_unordered_list(&lines, 0));
+        let offsets = |node: &location::SpanNode| (node.span.start.offset, node.span.end.offset);
+
+        assert_eq!(spans.iter().map(offsets).collect::<Vec<_>>(), vec![(0, 3), (4, 8), (9, 12)]);
+        assert_eq!(spans[0].children.iter().map(offsets).collect::<Vec<_>>(), vec![(2, 3)]);
+        assert_eq!(spans[1].children.iter().map(offsets).collect::<Vec<_>>(), vec![(4, 8)]);
+        assert_eq!(spans[1].children[0].children.iter().map(offsets).collect::<Vec<_>>(), vec![(7, 8)]);
+    }
+
+    #[test]
+    fn records_spans_of_definition_list_items() {
+        let text = "- t := *d*\n- u\n\n; v\n: w\nx";
+        let lines: Vec<&str> = text.lines().collect();
+        let offsets = |node: &location::SpanNode| (node.span.start.offset, node.span.end.offset);
+
+        let (_, spans, _) = location::record(text, || parse_dash_definition_list(&lines));
+        assert_eq!(spans.iter().map(offsets).collect::<Vec<_>>(), vec![(0, 3), (4, 10), (11, 14)]);
+        assert_eq!(spans[1].children.iter().map(offsets).collect::<Vec<_>>(), vec![(7, 10)]);
+
+        let (_, spans, _) = location::record(text, || parse_semicolon_definition_list(&lines[3..]));
+        assert_eq!(spans.iter().map(offsets).collect::<Vec<_>>(), vec![(16, 19), (20, 25)]);
     }
 }
@@ -1,60 +1,163 @@
 mod block_quotation;
 mod code_block;
 mod comment;
+mod div;
+mod figure;
+mod footnote;
 mod heading;
+mod html;
+mod line;
+mod link_alias;
 mod list;
 mod no_textile;
+mod note;
 mod paragraph;
 mod pre;
+mod table;
 
 use parser::{BlockElements, Block};
+use parser::diagnostic::Severity;
+use parser::location;
+pub use self::link_alias::{extract_link_aliases, resolve_link_aliases};
+pub use self::note::resolve_notes;
 use self::block_quotation::parse_block_quotation;
 use self::code_block::parse_code_block;
 use self::comment::parse_comment;
+use self::div::parse_div;
+use self::figure::parse_figure;
+use self::footnote::parse_footnote;
 use self::heading::parse_heading;
-use self::list::parse_list;
+use self::html::parse_html;
+use self::line::{BlockKind, classify};
+use self::list::{parse_definition_list, parse_list};
 use self::no_textile::parse_no_textile;
+use self::note::{parse_note, parse_note_list};
 use self::paragraph::parse_paragraph;
 use self::pre::parse_pre_block;
+use self::table::parse_table;
 
-pub fn parse_blocks(lines: &[&str]) -> BlockElements {
+pub fn parse_blocks<'a>(lines: &[&'a str]) -> BlockElements<'a> {
     let mut blocks = Vec::new();
     let mut cur_line = 0;
 
     while cur_line < lines.len() {
-        if let Some((block, consumed_lines)) = parse_block(&lines[cur_line..lines.len()]) {
-            blocks.push(block);
-            cur_line += consumed_lines;
+        // Trailing empty lines don't form any block.
+        if lines[cur_line..].iter().all(|line| line.is_empty()) {
+            break;
+        }
+
+        let mark = location::mark();
+
+        match parse_block(&lines[cur_line..lines.len()]) {
+            // Block must consume some lines, otherwise parsing never ends.
+            Some((block, consumed_lines)) if consumed_lines > 0 => {
+                location::push_lines(mark, &lines[cur_line..lines.len().min(cur_line + consumed_lines)]);
+                blocks.push(block);
+                cur_line += consumed_lines;
+            }
+            _ => {
+                location::discard(mark);
+                location::report(Severity::Error, "Line isn't parsed into any block and is skipped".to_string(), lines[cur_line]);
+                cur_line += 1;
+            }
         }
     }
     blocks
 }
 
-pub fn parse_block(lines: &[&str]) -> Option<(Block, usize)> {
-    lazy_static! {
-        static ref FNS: Vec<fn(&[&str]) -> Option<(Block, usize)>> = vec![
-            parse_block_quotation,
-            parse_code_block,
-            parse_comment,
-            parse_heading,
-            parse_list,
-            parse_no_textile,
-            parse_pre_block,
-            parse_paragraph,
-        ];
+pub fn parse_block<'a>(lines: &[&'a str]) -> Option<(Block<'a>, usize)> {
+    if lines.is_empty() {
+        return None;
     }
+
     // Get index of non-empty string.
-    let pos = lines.iter().position(|el| !el.is_empty());
-    let lines = match pos {
-        Some(value) => &lines[value..],
-        None => lines,
-    };
-
-    for f in FNS.iter() {
-        let res = f(lines);
-        if let Some(_) = res {
-            return res;
+    let pos = lines.iter().position(|el| !el.is_empty()).unwrap_or(0);
+    let lines = &lines[pos..];
+
+    let mark = location::mark();
+
+    // Only blocks, which signatures the first line matches, are parsed. Any other line starts paragraph.
+    for kind in classify(lines[0]).iter() {
+        // Skipped empty lines are consumed too.
+        if let Some((block, consumed_lines)) = parse_block_of_kind(kind, lines) {
+            return Some((block, pos + consumed_lines));
         }
+        location::discard(mark);
+    }
+    parse_paragraph(lines).map(|(block, consumed_lines)| (block, pos + consumed_lines))
+}
+
+fn parse_block_of_kind<'a>(kind: BlockKind, lines: &[&'a str]) -> Option<(Block<'a>, usize)> {
+    match kind {
+        BlockKind::BlockQuotation => parse_block_quotation(lines),
+        BlockKind::CodeBlock => parse_code_block(lines),
+        BlockKind::Comment => parse_comment(lines),
+        BlockKind::DefinitionList => parse_definition_list(lines),
+        BlockKind::Div => parse_div(lines),
+        BlockKind::Figure => parse_figure(lines),
+        BlockKind::Footnote => parse_footnote(lines),
+        BlockKind::Heading => parse_heading(lines),
+        BlockKind::Html => parse_html(lines),
+        BlockKind::List => parse_list(lines),
+        BlockKind::NoTextile => parse_no_textile(lines),
+        BlockKind::Note => parse_note(lines),
+        BlockKind::NoteList => parse_note_list(lines),
+        BlockKind::Pre => parse_pre_block(lines),
+        BlockKind::Table => parse_table(lines),
+    }
+}
+
+/// Checks that line starts block element other than paragraph without `p.` signature,
+/// e.g. `h1. Heading` or `p. Paragraph`. It is used to end extended blocks.
+pub fn is_block_start(line: &str) -> bool {
+    let kinds = classify(line);
+
+    if kinds.starts_with_p() || kinds.iter().any(BlockKind::is_certain) {
+        return true;
+    }
+
+    // Blocks, which need more than signature, are parsed from the line alone.
+    let mark = location::mark();
+    let res = kinds.iter().any(|kind| parse_block_of_kind(kind, &[line]).is_some());
+    location::discard(mark);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{Attributes, Block, Inline};
+    use super::*;
+
+    #[test]
+    fn parses_blocks_separated_by_empty_lines_correctly() {
+        assert_eq!(
+            parse_blocks(&["Text", "", "", "", "p. Text", "", ""]),
+            vec![
+                Block::Paragraph {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        Inline::Text("Text".into()),
+                    ],
+                    starts_with_p: false,
+                },
+                Block::Paragraph {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        Inline::Text("Text".into()),
+                    ],
+                    starts_with_p: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_next_block_if_signature_isnt_enough() {
+        // Line matches signatures of definition list and table row, but has no definitions.
+        assert!(matches!(parse_block(&["-(x). |a| (y) |"]), Some((Block::Table { .. }, 1))));
+        assert!(matches!(parse_block(&["fig. No image"]), Some((Block::Paragraph { .. }, 1))));
+        assert!(matches!(parse_block(&["fight. !img.png! now"]), Some((Block::Paragraph { .. }, 1))));
+        assert!(is_block_start("|a|"));
+        assert!(!is_block_start("table."));
     }
-    None
 }
@@ -0,0 +1,261 @@
+use parser::{Block, BlockElements, Inline, Note, NoteBacklinks};
+use parser::attributes::parse_block_attributes;
+use parser::inline::parse_inline_elements;
+use parser::patterns::{NOTE_PATTERN, NOTE_LIST_PATTERN};
+use parser::visit::{visit_blocks_mut, visit_inline_elements_mut, visit_inlines_mut};
+use std::mem;
+
+pub fn parse_note<'a>(lines: &[&'a str]) -> Option<(Block<'a>, usize)> {
+    let mut cur_line = 1;
+
+    if let Some(caps) = NOTE_PATTERN.captures(lines[0]) {
+        let mut strings = Vec::new();
+        strings.push(&lines[0][caps.at(0).unwrap_or("").len()..]);
+
+        for line in &lines[1..] {
+            cur_line += 1;
+            if line.is_empty() {
+                break;
+            }
+            strings.push(line);
+        }
+
+        Some((
+            Block::Note {
+                attributes: parse_block_attributes(caps.name("attributes").unwrap_or("")),
+                elements: parse_inline_elements(&strings),
+                label: caps.name("label").unwrap_or("").to_string(),
+            },
+            cur_line
+        ))
+    } else {
+        None
+    }
+}
+
+pub fn parse_note_list<'a>(lines: &[&'a str]) -> Option<(Block<'a>, usize)> {
+    if let Some(caps) = NOTE_LIST_PATTERN.captures(lines[0]) {
+        let flags = caps.name("flags").unwrap_or("");
+        let backlinks = if flags.contains('!') {
+            NoteBacklinks::Disabled
+        } else if flags.contains('^') {
+            NoteBacklinks::First
+        } else {
+            NoteBacklinks::All
+        };
+        let consumed_lines = if lines.len() > 1 && lines[1].is_empty() { 2 } else { 1 };
+
+        Some((
+            Block::NoteList {
+                attributes: parse_block_attributes(caps.name("attributes").unwrap_or("")),
+                backlinks,
+                elements: Vec::new(),
+                symbol: caps.name("symbol").map(|symbol| symbol.to_string()),
+                unreferenced: flags.contains('+'),
+            },
+            consumed_lines
+        ))
+    } else {
+        None
+    }
+}
+
+/// Numbers endnote references in document order and moves endnotes into lists of notes.
+pub fn resolve_notes(blocks: &mut BlockElements) {
+    let mut notes = Vec::new();
+
+    for block in mem::take(blocks) {
+        match block {
+            Block::Note { attributes, elements, label } => {
+                if !notes.iter().any(|note: &Note| note.label == label) {
+                    notes.push(Note {
+                        attributes,
+                        elements,
+                        label,
+                        number: None,
+                        references: 0,
+                    });
+                }
+            }
+            block => blocks.push(block),
+        }
+    }
+
+    // Every referenced label with count of its references. Number of note is index + 1.
+    let mut labels: Vec<(String, usize)> = Vec::new();
+    {
+        let mut number_reference = |element: &mut Inline| {
+            if let Inline::NoteRef { ref label, ref mut number, ref mut reference, .. } = *element {
+                let idx = match labels.iter().position(|el| el.0 == *label) {
+                    Some(idx) => idx,
+                    None => {
+                        labels.push((label.to_string(), 0));
+                        labels.len() - 1
+                    }
+                };
+                labels[idx].1 += 1;
+                *number = idx + 1;
+                *reference = labels[idx].1;
+            }
+        };
+        visit_inline_elements_mut(blocks, &mut number_reference);
+
+        for note in &mut notes {
+            visit_inlines_mut(&mut note.elements, &mut number_reference);
+        }
+    }
+
+    for note in &mut notes {
+        if let Some(idx) = labels.iter().position(|el| el.0 == note.label) {
+            note.number = Some(idx + 1);
+            note.references = labels[idx].1;
+        }
+    }
+    // Referenced notes go first in order of numbers, then unreferenced notes in order of definition.
+    notes.sort_by_key(|note| note.number.unwrap_or(usize::MAX));
+
+    visit_blocks_mut(blocks, &mut |block| {
+        if let Block::NoteList { ref mut elements, unreferenced, .. } = *block {
+            *elements = notes.iter()
+                .filter(|note| unreferenced || note.number.is_some())
+                .cloned()
+                .collect();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{Attributes, Block, Inline, Note, NoteBacklinks, SpanNode, parse_with_spans};
+    use super::*;
+
+    #[test]
+    fn parses_note_correctly() {
+        assert_eq!(
+            parse_note(&["note#intro(my-note). Note text"]),
+            Some((
+                Block::Note {
+                    attributes: hashmap!{
+                        "class".to_string() => "my-note".to_string(),
+                    },
+                    elements: vec![
+                        Inline::Text("Note text".into()),
+                    ],
+                    label: "intro".to_string(),
+                },
+                1
+            ))
+        );
+    }
+
+    #[test]
+    fn parses_note_list_correctly() {
+        assert_eq!(
+            parse_note_list(&["notelist:§^+.", ""]),
+            Some((
+                Block::NoteList {
+                    attributes: Attributes::new(),
+                    backlinks: NoteBacklinks::First,
+                    elements: Vec::new(),
+                    symbol: Some("§".to_string()),
+                    unreferenced: true,
+                },
+                2
+            ))
+        );
+    }
+
+    #[test]
+    fn resolves_notes_correctly() {
+        let mut blocks = vec![
+            Block::NoteList {
+                attributes: Attributes::new(),
+                backlinks: NoteBacklinks::All,
+                elements: Vec::new(),
+                symbol: None,
+                unreferenced: true,
+            },
+            Block::Note {
+                attributes: Attributes::new(),
+                elements: vec![Inline::Text("Unused".into())],
+                label: "unused".to_string(),
+            },
+            Block::Note {
+                attributes: Attributes::new(),
+                elements: vec![Inline::Text("Second".into())],
+                label: "b".to_string(),
+            },
+            Block::Paragraph {
+                attributes: Attributes::new(),
+                elements: parse_inline_elements(&["Text[#b] and[#a] and[#b]"]),
+                starts_with_p: false,
+            },
+        ];
+        resolve_notes(&mut blocks);
+
+        assert_eq!(
+            blocks,
+            vec![
+                Block::NoteList {
+                    attributes: Attributes::new(),
+                    backlinks: NoteBacklinks::All,
+                    elements: vec![
+                        Note {
+                            attributes: Attributes::new(),
+                            elements: vec![Inline::Text("Second".into())],
+                            label: "b".to_string(),
+                            number: Some(1),
+                            references: 2,
+                        },
+                        Note {
+                            attributes: Attributes::new(),
+                            elements: vec![Inline::Text("Unused".into())],
+                            label: "unused".to_string(),
+                            number: None,
+                            references: 0,
+                        },
+                    ],
+                    symbol: None,
+                    unreferenced: true,
+                },
+                Block::Paragraph {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        Inline::Text("Text".into()),
+                        Inline::NoteRef {
+                            label: "b".to_string(),
+                            link: true,
+                            number: 1,
+                            reference: 1,
+                        },
+                        Inline::Text(" and".into()),
+                        Inline::NoteRef {
+                            label: "a".to_string(),
+                            link: true,
+                            number: 2,
+                            reference: 1,
+                        },
+                        Inline::Text(" and".into()),
+                        Inline::NoteRef {
+                            label: "b".to_string(),
+                            link: true,
+                            number: 1,
+                            reference: 2,
+                        },
+                    ],
+                    starts_with_p: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn records_spans_of_notes_in_note_lists() {
+        let (_, spans) = parse_with_spans("Ref[#a]\n\nnote#b. B\n\nnote#a. A\n\nnotelist+.");
+        let offsets = |node: &SpanNode| (node.span.start.offset, node.span.end.offset);
+
+        assert_eq!(spans.iter().map(offsets).collect::<Vec<_>>(), vec![(0, 7), (31, 41)]);
+        assert_eq!(spans[1].children.iter().map(offsets).collect::<Vec<_>>(), vec![(20, 29), (9, 18)]);
+        assert_eq!(spans[1].children[0].children.iter().map(offsets).collect::<Vec<_>>(), vec![(28, 29)]);
+    }
+}
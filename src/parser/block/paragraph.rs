@@ -3,15 +3,14 @@ use parser::attributes::parse_block_attributes;
 use parser::inline::parse_inline_elements;
 use parser::patterns::PARAGRAPH_PATTERN;
 
-pub fn parse_paragraph(lines: &[&str]) -> Option<(Block, usize)> {
+pub fn parse_paragraph<'a>(lines: &[&'a str]) -> Option<(Block<'a>, usize)> {
     let mut cur_line = 1;
     let mut attributes = "";
     let mut strings = Vec::new();
 
-    if PARAGRAPH_PATTERN.is_match(lines[0]) {
-        let caps = PARAGRAPH_PATTERN.captures(lines[0]).unwrap();
+    if let Some(caps) = PARAGRAPH_PATTERN.captures(lines[0]) {
         attributes = caps.name("attributes").unwrap_or("");
-        strings.push(&lines[0][caps.at(0).unwrap().len()..]);
+        strings.push(&lines[0][caps.at(0).unwrap_or("").len()..]);
 
         for line in &lines[1..] {
             cur_line += 1;
@@ -25,7 +24,7 @@ pub fn parse_paragraph(lines: &[&str]) -> Option<(Block, usize)> {
         Block::Paragraph {
             attributes: parse_block_attributes(attributes),
             elements: parse_inline_elements(&strings),
-            starts_with_p: PARAGRAPH_PATTERN.find(lines[0]).unwrap().1 != 0,
+            starts_with_p: PARAGRAPH_PATTERN.find(lines[0]).is_some_and(|(_, end)| end != 0),
         },
         cur_line
     ))
@@ -44,9 +43,9 @@ mod tests {
                 Block::Paragraph {
                     attributes: Attributes::new(),
                     elements: vec![
-                        Inline::Text("Paragraph".to_string()),
+                        Inline::Text("Paragraph".into()),
                         Inline::Break,
-                        Inline::Text("with text".to_string()),
+                        Inline::Text("with text".into()),
                     ],
                     starts_with_p: true,
                 },
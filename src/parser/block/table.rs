@@ -0,0 +1,408 @@
+use parser::{Attributes, Block, TableCell, TableElement};
+use parser::attributes::{parse_block_attributes, parse_table_attributes};
+use parser::inline::parse_inline_elements;
+use parser::location;
+use parser::patterns::{
+    TABLE_PATTERN,
+    TABLE_CAPTION_PATTERN,
+    TABLE_COLGROUP_PATTERN,
+    TABLE_COL_PATTERN,
+    TABLE_ROW_PATTERN,
+    TABLE_CELL_PATTERN,
+    TABLE_SECTION_PATTERN
+};
+
+pub fn parse_table<'a>(lines: &[&'a str]) -> Option<(Block<'a>, usize)> {
+    let mut cur_line = 0;
+    let mut attributes = Attributes::new();
+    let mut elements = Vec::new();
+    let mut section = None;
+    // Mark and index of the first line of open section.
+    let mut section_start = (location::mark(), 0);
+    let mut has_rows = false;
+
+    if let Some(caps) = TABLE_PATTERN.captures(lines[0]) {
+        attributes = parse_block_attributes(caps.name("attributes").unwrap_or(""));
+        cur_line += 1;
+    }
+
+    // Caption is allowed only before other table elements.
+    if let Some(caps) = lines.get(cur_line).and_then(|line| TABLE_CAPTION_PATTERN.captures(line)) {
+        let mark = location::mark();
+        elements.push(TableElement::Caption {
+            attributes: parse_block_attributes(caps.name("attributes").unwrap_or("")),
+            elements: parse_inline_elements(&[caps.name("string").unwrap_or("").trim()]),
+        });
+        location::push(mark, location::locate(lines[cur_line]));
+        cur_line += 1;
+    }
+
+    while cur_line < lines.len() {
+        let line = lines[cur_line];
+
+        if let Some(caps) = TABLE_SECTION_PATTERN.captures(line) {
+            if let Some(section) = section.take() {
+                elements.push(section);
+                location::push_lines(section_start.0, &lines[section_start.1..cur_line]);
+            }
+            section_start = (location::mark(), cur_line);
+            section = Some(TableElement::Section {
+                attributes: parse_table_attributes(caps.name("attributes").unwrap_or("")),
+                rows: Vec::new(),
+                tag_type: match caps.name("section").unwrap_or("") {
+                    "^" => "thead",
+                    "~" => "tfoot",
+                    _ => "tbody",
+                }.to_string(),
+            });
+        } else if let Some(caps) = TABLE_COLGROUP_PATTERN.captures(line) {
+            let mark = location::mark();
+            elements.push(parse_table_colgroup(caps.name("spec").unwrap_or("")));
+            location::push(mark, location::locate(line));
+        } else if let Some(caps) = TABLE_ROW_PATTERN.captures(line) {
+            let mark = location::mark();
+            let row = TableElement::Row {
+                attributes: parse_table_attributes(caps.name("attributes").unwrap_or("")),
+                cells: caps.name("cells").unwrap_or("").split('|').map(parse_table_cell).collect(),
+            };
+            location::push(mark, location::locate(line));
+
+            match section {
+                Some(TableElement::Section { ref mut rows, .. }) => rows.push(row),
+                _ => elements.push(row),
+            }
+            has_rows = true;
+        } else {
+            break;
+        }
+        cur_line += 1;
+    }
+
+    if let Some(section) = section {
+        elements.push(section);
+        location::push_lines(section_start.0, &lines[section_start.1..cur_line]);
+    }
+
+    if !has_rows {
+        return None;
+    }
+
+    // Skip empty line after table.
+    if cur_line < lines.len() && lines[cur_line].is_empty() {
+        cur_line += 1;
+    }
+
+    Some((
+        Block::Table {
+            attributes,
+            elements,
+        },
+        cur_line
+    ))
+}
+
+fn parse_table_cell(text: &str) -> TableCell<'_> {
+    let mark = location::mark();
+    let cell = parse_table_cell_elements(text);
+    location::push(mark, location::locate(text));
+    cell
+}
+
+fn parse_table_cell_elements(text: &str) -> TableCell<'_> {
+    if let Some(caps) = TABLE_CELL_PATTERN.captures(text) {
+        let attrs_str = caps.name("attributes").unwrap_or("");
+        let elements = parse_inline_elements(&[text[caps.at(0).unwrap_or("").len()..].trim()]);
+
+        if caps.name("header").is_some() {
+            return TableCell::Header {
+                attributes: parse_table_attributes(attrs_str),
+                elements,
+            };
+        } else if !attrs_str.is_empty() {
+            return TableCell::Data {
+                attributes: parse_table_attributes(attrs_str),
+                elements,
+            };
+        }
+    }
+
+    TableCell::Data {
+        attributes: Attributes::new(),
+        elements: parse_inline_elements(&[text.trim()]),
+    }
+}
+
+/// Parses group of columns. The first column spec contains attributes of the whole group.
+fn parse_table_colgroup(spec: &str) -> TableElement<'_> {
+    let mut specs = spec.split('|').map(parse_table_col);
+
+    TableElement::ColGroup {
+        attributes: specs.next().unwrap_or_default(),
+        cols: specs.collect(),
+    }
+}
+
+fn parse_table_col(spec: &str) -> Attributes {
+    match TABLE_COL_PATTERN.captures(spec.trim()) {
+        Some(caps) => {
+            let mut attrs = parse_table_attributes(caps.name("attributes").unwrap_or(""));
+
+            if let Some(span) = attrs.remove("colspan") {
+                attrs.insert("span".to_string(), span);
+            }
+
+            if let Some(width) = caps.name("width") {
+                attrs.insert("width".to_string(), width.to_string());
+            }
+            attrs
+        }
+        None => Attributes::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{Attributes, Block, Inline, TableCell, TableElement};
+    use super::*;
+
+    #[test]
+    fn parses_table_correctly() {
+        assert_eq!(
+            parse_table(&["|_. Name|_. Age|", "|John|*25*|"]),
+            Some((
+                Block::Table {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        TableElement::Row {
+                            attributes: Attributes::new(),
+                            cells: vec![
+                                TableCell::Header {
+                                    attributes: Attributes::new(),
+                                    elements: vec![
+                                        Inline::Text("Name".into()),
+                                    ],
+                                },
+                                TableCell::Header {
+                                    attributes: Attributes::new(),
+                                    elements: vec![
+                                        Inline::Text("Age".into()),
+                                    ],
+                                },
+                            ],
+                        },
+                        TableElement::Row {
+                            attributes: Attributes::new(),
+                            cells: vec![
+                                TableCell::Data {
+                                    attributes: Attributes::new(),
+                                    elements: vec![
+                                        Inline::Text("John".into()),
+                                    ],
+                                },
+                                TableCell::Data {
+                                    attributes: Attributes::new(),
+                                    elements: vec![
+                                        Inline::Bold {
+                                            attributes: Attributes::new(),
+                                            elements: vec![
+                                                Inline::Text("25".into()),
+                                            ],
+                                            tag_type: "strong".to_string(),
+                                        },
+                                    ],
+                                },
+                            ],
+                        },
+                    ],
+                },
+                2
+            ))
+        );
+    }
+
+    #[test]
+    fn parses_table_with_attributes_correctly() {
+        assert_eq!(
+            parse_table(&["table(my-table).", "(row#first). |>. Right|{color: red}. Red|", "", "p. Paragraph"]),
+            Some((
+                Block::Table {
+                    attributes: hashmap!{
+                        "class".to_string() => "my-table".to_string(),
+                    },
+                    elements: vec![
+                        TableElement::Row {
+                            attributes: hashmap!{
+                                "class".to_string() => "row".to_string(),
+                                "id".to_string() => "first".to_string(),
+                            },
+                            cells: vec![
+                                TableCell::Data {
+                                    attributes: hashmap!{
+                                        "style".to_string() => "text-align: right".to_string(),
+                                    },
+                                    elements: vec![
+                                        Inline::Text("Right".into()),
+                                    ],
+                                },
+                                TableCell::Data {
+                                    attributes: hashmap!{
+                                        "style".to_string() => "color: red".to_string(),
+                                    },
+                                    elements: vec![
+                                        Inline::Text("Red".into()),
+                                    ],
+                                },
+                            ],
+                        },
+                    ],
+                },
+                3
+            ))
+        );
+    }
+
+    #[test]
+    fn not_parse_text_with_dot_as_cell_attributes() {
+        assert_eq!(
+            parse_table_cell("Mr. Smith"),
+            TableCell::Data {
+                attributes: Attributes::new(),
+                elements: vec![
+                    Inline::Text("Mr. Smith".into()),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn not_parse_table_without_rows() {
+        assert_eq!(parse_table(&["table(my-table).", "Paragraph"]), None);
+    }
+
+    #[test]
+    fn parses_table_with_spans_and_vertical_align_correctly() {
+        assert_eq!(
+            parse_table_cell("\\2/3^. Merged"),
+            TableCell::Data {
+                attributes: hashmap!{
+                    "colspan".to_string() => "2".to_string(),
+                    "rowspan".to_string() => "3".to_string(),
+                    "style".to_string() => "vertical-align: top".to_string(),
+                },
+                elements: vec![
+                    Inline::Text("Merged".into()),
+                ],
+            }
+        );
+        assert_eq!(
+            parse_table_cell("_\\2. Merged header"),
+            TableCell::Header {
+                attributes: hashmap!{
+                    "colspan".to_string() => "2".to_string(),
+                },
+                elements: vec![
+                    Inline::Text("Merged header".into()),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn parses_table_with_caption_colgroup_and_sections_correctly() {
+        assert_eq!(
+            parse_table(&["|=. Caption", "|:\\2. 100|50|", "|^.", "|_. Head|", "|-(body).", "|Body|", "|~.", "|Foot|"]),
+            Some((
+                Block::Table {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        TableElement::Caption {
+                            attributes: Attributes::new(),
+                            elements: vec![
+                                Inline::Text("Caption".into()),
+                            ],
+                        },
+                        TableElement::ColGroup {
+                            attributes: hashmap!{
+                                "span".to_string() => "2".to_string(),
+                                "width".to_string() => "100".to_string(),
+                            },
+                            cols: vec![
+                                hashmap!{
+                                    "width".to_string() => "50".to_string(),
+                                },
+                            ],
+                        },
+                        TableElement::Section {
+                            attributes: Attributes::new(),
+                            rows: vec![
+                                TableElement::Row {
+                                    attributes: Attributes::new(),
+                                    cells: vec![
+                                        TableCell::Header {
+                                            attributes: Attributes::new(),
+                                            elements: vec![
+                                                Inline::Text("Head".into()),
+                                            ],
+                                        },
+                                    ],
+                                },
+                            ],
+                            tag_type: "thead".to_string(),
+                        },
+                        TableElement::Section {
+                            attributes: hashmap!{
+                                "class".to_string() => "body".to_string(),
+                            },
+                            rows: vec![
+                                TableElement::Row {
+                                    attributes: Attributes::new(),
+                                    cells: vec![
+                                        TableCell::Data {
+                                            attributes: Attributes::new(),
+                                            elements: vec![
+                                                Inline::Text("Body".into()),
+                                            ],
+                                        },
+                                    ],
+                                },
+                            ],
+                            tag_type: "tbody".to_string(),
+                        },
+                        TableElement::Section {
+                            attributes: Attributes::new(),
+                            rows: vec![
+                                TableElement::Row {
+                                    attributes: Attributes::new(),
+                                    cells: vec![
+                                        TableCell::Data {
+                                            attributes: Attributes::new(),
+                                            elements: vec![
+                                                Inline::Text("Foot".into()),
+                                            ],
+                                        },
+                                    ],
+                                },
+                            ],
+                            tag_type: "tfoot".to_string(),
+                        },
+                    ],
+                },
+                8
+            ))
+        );
+    }
+
+    #[test]
+    fn records_spans_of_rows_and_cells() {
+        let text = "|a|*b*|\n|^.\n|c|d|";
+        let lines: Vec<&str> = text.lines().collect();
+        let (_, spans, _) = location::record(text, || parse_table(&lines));
+        let offsets = |node: &location::SpanNode| (node.span.start.offset, node.span.end.offset);
+
+        assert_eq!(spans.iter().map(offsets).collect::<Vec<_>>(), vec![(0, 7), (8, 17)]);
+        assert_eq!(spans[0].children.iter().map(offsets).collect::<Vec<_>>(), vec![(1, 2), (3, 6)]);
+        assert_eq!(spans[0].children[1].children.iter().map(offsets).collect::<Vec<_>>(), vec![(3, 6)]);
+        assert_eq!(spans[1].children.iter().map(offsets).collect::<Vec<_>>(), vec![(12, 17)]);
+        assert_eq!(spans[1].children[0].children.iter().map(offsets).collect::<Vec<_>>(), vec![(13, 14), (15, 16)]);
+    }
+}
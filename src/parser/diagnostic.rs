@@ -0,0 +1,21 @@
+//! Problems in markup found during parsing.
+
+use parser::Span;
+
+/// Severity of problem in markup.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    /// Part of markup is ignored, e.g. malformed attributes.
+    Error,
+    /// Markup is rendered differently than probably intended, e.g. unclosed bold text is rendered as text.
+    Warning,
+}
+
+/// Problem in markup, e.g. unclosed bold text or malformed attributes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// Location of problem. It is `None` if problem is found in text, which isn't taken from source as is.
+    pub span: Option<Span>,
+}
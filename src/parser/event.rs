@@ -0,0 +1,648 @@
+//! Pull parser, which yields events of elements instead of building the whole tree.
+
+use parser::{Attributes, Block, BlockElements, DefinitionListElement, Inline, LinkAliases, ListElement, Note,
+             NoteBacklinks, TableCell, TableElement};
+use parser::block::{extract_link_aliases, parse_block, parse_blocks, resolve_link_aliases, resolve_notes};
+use parser::patterns::{NOTE_LIST_PATTERN, NOTE_PATTERN};
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::vec;
+
+/// Element with children. Every `Event::Start` is followed by events of children and `Event::End` with the same tag.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Tag {
+    /// Block quotation with block elements.
+    BlockQuotation(Attributes),
+    /// Code block with code as single text.
+    CodeBlock(Attributes),
+    /// Comment block with text of every line.
+    Comment,
+    /// Definition list with terms and definitions.
+    DefinitionList(Attributes),
+    Definition(Attributes),
+    DefinitionTerm(Attributes),
+    /// Division with block elements.
+    Div(Attributes),
+    /// Figure with image as the first inline element, which is followed by caption.
+    Figure(Attributes),
+    Footnote {
+        attributes: Attributes,
+        backlink: bool,
+        id: String,
+    },
+    Heading {
+        attributes: Attributes,
+        level: u8,
+    },
+    /// Raw HTML block with text of every line.
+    Html,
+    /// Endnote, which isn't moved into list of notes yet.
+    Note {
+        attributes: Attributes,
+        label: String,
+    },
+    /// List of endnotes with `NoteListItem` elements.
+    NoteList {
+        attributes: Attributes,
+        backlinks: NoteBacklinks,
+        symbol: Option<String>,
+        unreferenced: bool,
+    },
+    NoteListItem {
+        attributes: Attributes,
+        label: String,
+        number: Option<usize>,
+        references: usize,
+    },
+    /// Block with disabled Textile formatting with text of every line.
+    NoTextile,
+    /// Ordered list with list items and nested lists.
+    OrderedList {
+        attributes: Attributes,
+        level: u8,
+    },
+    ListItem(Attributes),
+    Paragraph {
+        attributes: Attributes,
+        starts_with_p: bool,
+    },
+    /// Pre-formatted text with text of every line.
+    Pre(Attributes),
+    Table(Attributes),
+    TableCaption(Attributes),
+    /// Group of columns. It doesn't have children.
+    TableColGroup {
+        attributes: Attributes,
+        cols: Vec<Attributes>,
+    },
+    TableData(Attributes),
+    TableHeader(Attributes),
+    TableRow(Attributes),
+    TableSection {
+        attributes: Attributes,
+        tag_type: String,
+    },
+    /// Unordered list with list items and nested lists.
+    UnorderedList {
+        attributes: Attributes,
+        level: u8,
+    },
+    Bold {
+        attributes: Attributes,
+        tag_type: String,
+    },
+    Citation(Attributes),
+    Italic {
+        attributes: Attributes,
+        tag_type: String,
+    },
+    Link(Attributes),
+    Span(Attributes),
+    Strikethrough(Attributes),
+    Subscript(Attributes),
+    Superscript(Attributes),
+    Underlined(Attributes),
+}
+
+/// Event of pull parser.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event<'a> {
+    /// Start of element with children.
+    Start(Tag),
+    /// End of element with children.
+    End(Tag),
+    /// String with text, which is borrowed from document, if it's possible.
+    Text(Cow<'a, str>),
+    /// Inline element without children, e.g. code, image or line break.
+    Inline(Inline<'a>),
+}
+
+/// Pull parser, which yields events of elements in document order.
+///
+/// Blocks are parsed one by one, when their events are requested. If document has endnotes,
+/// it's parsed at once, because endnotes are numbered by references in the whole document.
+///
+/// # Example
+///
+/// ```rust
+/// use textile::parser::*;
+///
+/// let headings: Vec<String> = Parser::new("h1. Title\n\nText\n\nh2. Section")
+///     .filter_map(|event| match event {
+///         Event::Start(Tag::Heading { level, .. }) => Some(format!("h{}", level)),
+///         _ => None,
+///     })
+///     .collect();
+/// assert_eq!(headings, vec!["h1".to_string(), "h2".to_string()]);
+/// ```
+pub struct Parser<'a> {
+    source: Source<'a>,
+    events: VecDeque<Event<'a>>,
+}
+
+enum Source<'a> {
+    Blocks(vec::IntoIter<Block<'a>>),
+    Lines {
+        aliases: LinkAliases,
+        cur_line: usize,
+        lines: Vec<&'a str>,
+    },
+}
+
+impl<'a> Parser<'a> {
+    /// Creates parser of text.
+    pub fn new(text: &'a str) -> Parser<'a> {
+        let (lines, aliases) = extract_link_aliases(&text.lines().collect::<Vec<&str>>());
+        let has_notes = text.contains("[#") ||
+                        lines.iter().any(|line| NOTE_PATTERN.is_match(line) || NOTE_LIST_PATTERN.is_match(line));
+
+        let source = if has_notes {
+            let mut blocks = parse_blocks(&lines);
+            resolve_link_aliases(&mut blocks, &aliases);
+            resolve_notes(&mut blocks);
+            Source::Blocks(blocks.into_iter())
+        } else {
+            Source::Lines { aliases, cur_line: 0, lines }
+        };
+
+        Parser { source, events: VecDeque::new() }
+    }
+
+    /// Creates parser, which yields events of already parsed elements.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use textile::parser::*;
+    ///
+    /// let blocks = parse("Some *bold* text");
+    /// assert_eq!(from_events(Parser::from_blocks(blocks.clone())), blocks);
+    /// ```
+    pub fn from_blocks(blocks: BlockElements<'a>) -> Parser<'a> {
+        Parser {
+            source: Source::Blocks(blocks.into_iter()),
+            events: VecDeque::new(),
+        }
+    }
+
+    fn next_block(&mut self) -> Option<Block<'a>> {
+        match self.source {
+            Source::Blocks(ref mut blocks) => blocks.next(),
+            Source::Lines { ref aliases, ref mut cur_line, ref lines } => {
+                while *cur_line < lines.len() && !lines[*cur_line..].iter().all(|line| line.is_empty()) {
+                    match parse_block(&lines[*cur_line..]) {
+                        Some((block, consumed_lines)) if consumed_lines > 0 => {
+                            let mut blocks = vec![block];
+                            resolve_link_aliases(&mut blocks, aliases);
+                            *cur_line += consumed_lines;
+                            return blocks.pop();
+                        }
+                        _ => *cur_line += 1,
+                    }
+                }
+                None
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Parser<'a> {
+    type Item = Event<'a>;
+
+    fn next(&mut self) -> Option<Event<'a>> {
+        if self.events.is_empty() {
+            if let Some(block) = self.next_block() {
+                push_block(&mut self.events, block);
+            }
+        }
+        self.events.pop_front()
+    }
+}
+
+fn push_container<'a, F: FnOnce(&mut VecDeque<Event<'a>>)>(events: &mut VecDeque<Event<'a>>, tag: Tag, f: F) {
+    events.push_back(Event::Start(tag.clone()));
+    f(events);
+    events.push_back(Event::End(tag));
+}
+
+fn push_lines(events: &mut VecDeque<Event>, tag: Tag, lines: Vec<String>) {
+    push_container(events, tag, |events| events.extend(lines.into_iter().map(|line| Event::Text(line.into()))));
+}
+
+fn push_inlines<'a>(events: &mut VecDeque<Event<'a>>, tag: Tag, elements: Vec<Inline<'a>>) {
+    push_container(events, tag, |events| {
+        for element in elements {
+            push_inline(events, element);
+        }
+    });
+}
+
+fn push_block<'a>(events: &mut VecDeque<Event<'a>>, block: Block<'a>) {
+    match block {
+        Block::BlockQuotation { attributes, elements } => {
+            push_container(events, Tag::BlockQuotation(attributes), |events| {
+                for element in elements {
+                    push_block(events, element);
+                }
+            });
+        }
+        Block::CodeBlock { attributes, code } => {
+            push_container(events, Tag::CodeBlock(attributes), |events| events.push_back(Event::Text(code)));
+        }
+        Block::Comment(lines) => push_lines(events, Tag::Comment, lines),
+        Block::DefinitionList { attributes, elements } => {
+            push_container(events, Tag::DefinitionList(attributes), |events| {
+                for element in elements {
+                    match element {
+                        DefinitionListElement::Definition { attributes, elements } => {
+                            push_inlines(events, Tag::Definition(attributes), elements);
+                        }
+                        DefinitionListElement::Term { attributes, elements } => {
+                            push_inlines(events, Tag::DefinitionTerm(attributes), elements);
+                        }
+                    }
+                }
+            });
+        }
+        Block::Div { attributes, elements } => {
+            push_container(events, Tag::Div(attributes), |events| {
+                for element in elements {
+                    push_block(events, element);
+                }
+            });
+        }
+        Block::Figure { attributes, caption, image } => {
+            let mut elements = vec![*image];
+            elements.extend(caption);
+            push_inlines(events, Tag::Figure(attributes), elements);
+        }
+        Block::Footnote { attributes, backlink, elements, id } => {
+            push_inlines(events, Tag::Footnote { attributes, backlink, id }, elements);
+        }
+        Block::Heading { attributes, elements, level } => {
+            push_inlines(events, Tag::Heading { attributes, level }, elements);
+        }
+        Block::Html(lines) => push_lines(events, Tag::Html, lines),
+        Block::NoTextileBlock(lines) => push_lines(events, Tag::NoTextile, lines),
+        Block::Note { attributes, elements, label } => {
+            push_inlines(events, Tag::Note { attributes, label }, elements);
+        }
+        Block::NoteList { attributes, backlinks, elements, symbol, unreferenced } => {
+            push_container(events, Tag::NoteList { attributes, backlinks, symbol, unreferenced }, |events| {
+                for note in elements {
+                    let tag = Tag::NoteListItem {
+                        attributes: note.attributes,
+                        label: note.label,
+                        number: note.number,
+                        references: note.references,
+                    };
+                    push_inlines(events, tag, note.elements);
+                }
+            });
+        }
+        Block::OrderedList { attributes, elements, level } => {
+            push_list(events, Tag::OrderedList { attributes, level }, elements);
+        }
+        Block::Paragraph { attributes, elements, starts_with_p } => {
+            push_inlines(events, Tag::Paragraph { attributes, starts_with_p }, elements);
+        }
+        Block::Pre { attributes, lines } => push_lines(events, Tag::Pre(attributes), lines),
+        Block::Table { attributes, elements } => {
+            push_container(events, Tag::Table(attributes), |events| {
+                for element in elements {
+                    push_table_element(events, element);
+                }
+            });
+        }
+        Block::UnorderedList { attributes, elements, level } => {
+            push_list(events, Tag::UnorderedList { attributes, level }, elements);
+        }
+    }
+}
+
+fn push_list<'a>(events: &mut VecDeque<Event<'a>>, tag: Tag, elements: Vec<ListElement<'a>>) {
+    push_container(events, tag, |events| {
+        for element in elements {
+            match element {
+                ListElement::ListItem { attributes, elements } => push_inlines(events, Tag::ListItem(attributes), elements),
+                ListElement::List(list) => push_block(events, list),
+            }
+        }
+    });
+}
+
+fn push_table_element<'a>(events: &mut VecDeque<Event<'a>>, element: TableElement<'a>) {
+    match element {
+        TableElement::Caption { attributes, elements } => push_inlines(events, Tag::TableCaption(attributes), elements),
+        TableElement::ColGroup { attributes, cols } => {
+            push_container(events, Tag::TableColGroup { attributes, cols }, |_| {});
+        }
+        TableElement::Row { attributes, cells } => {
+            push_container(events, Tag::TableRow(attributes), |events| {
+                for cell in cells {
+                    match cell {
+                        TableCell::Data { attributes, elements } => push_inlines(events, Tag::TableData(attributes), elements),
+                        TableCell::Header { attributes, elements } => {
+                            push_inlines(events, Tag::TableHeader(attributes), elements);
+                        }
+                    }
+                }
+            });
+        }
+        TableElement::Section { attributes, rows, tag_type } => {
+            push_container(events, Tag::TableSection { attributes, tag_type }, |events| {
+                for row in rows {
+                    push_table_element(events, row);
+                }
+            });
+        }
+    }
+}
+
+fn push_inline<'a>(events: &mut VecDeque<Event<'a>>, element: Inline<'a>) {
+    match element {
+        Inline::Bold { attributes, elements, tag_type } => push_inlines(events, Tag::Bold { attributes, tag_type }, elements),
+        Inline::Citation { attributes, elements } => push_inlines(events, Tag::Citation(attributes), elements),
+        Inline::Italic { attributes, elements, tag_type } => {
+            push_inlines(events, Tag::Italic { attributes, tag_type }, elements);
+        }
+        Inline::Link { attributes, elements } => push_inlines(events, Tag::Link(attributes), elements),
+        Inline::Span { attributes, elements } => push_inlines(events, Tag::Span(attributes), elements),
+        Inline::Strikethrough { attributes, elements } => push_inlines(events, Tag::Strikethrough(attributes), elements),
+        Inline::Subscript { attributes, elements } => push_inlines(events, Tag::Subscript(attributes), elements),
+        Inline::Superscript { attributes, elements } => push_inlines(events, Tag::Superscript(attributes), elements),
+        Inline::Text(text) => events.push_back(Event::Text(text)),
+        Inline::Underlined { attributes, elements } => push_inlines(events, Tag::Underlined(attributes), elements),
+        element => events.push_back(Event::Inline(element)),
+    }
+}
+
+/// Element built from events, which is placed into its parent element.
+enum Node<'a> {
+    Block(Block<'a>),
+    DefinitionListElement(DefinitionListElement<'a>),
+    Inline(Inline<'a>),
+    ListElement(ListElement<'a>),
+    Note(Note<'a>),
+    TableCell(TableCell<'a>),
+    TableElement(TableElement<'a>),
+}
+
+/// Builds elements from events, e.g. from events of `Parser` changed by iterator adapters.
+/// Missing `End` events are added at the end of stream, extra `End` events are skipped.
+/// Children, which can't be placed into their parent, e.g. text outside of block, are skipped too.
+///
+/// # Example
+///
+/// ```rust
+/// use textile::parser::*;
+///
+/// let events = Parser::new("Some \"link\":http://example.com").map(|event| match event {
+///     Event::Start(Tag::Link(_)) => Event::Start(Tag::Bold { attributes: Attributes::new(), tag_type: "b".to_string() }),
+///     event => event,
+/// });
+/// assert_eq!(from_events(events), parse("Some **link**"));
+/// ```
+pub fn from_events<'a, I: IntoIterator<Item = Event<'a>>>(events: I) -> BlockElements<'a> {
+    let mut stack: Vec<(Tag, Vec<Node>)> = Vec::new();
+    let mut blocks = Vec::new();
+
+    for event in events {
+        let node = match event {
+            Event::Start(tag) => {
+                stack.push((tag, Vec::new()));
+                continue;
+            }
+            // Start tag is used, so adapters may change only start events of elements.
+            Event::End(_) => match stack.pop() {
+                Some((tag, children)) => build_node(tag, children),
+                None => continue,
+            },
+            Event::Text(text) => Node::Inline(Inline::Text(text)),
+            Event::Inline(element) => Node::Inline(element),
+        };
+        add_node(&mut stack, &mut blocks, node);
+    }
+
+    while let Some((tag, children)) = stack.pop() {
+        let node = build_node(tag, children);
+        add_node(&mut stack, &mut blocks, node);
+    }
+    blocks
+}
+
+fn add_node<'a>(stack: &mut [(Tag, Vec<Node<'a>>)], blocks: &mut BlockElements<'a>, node: Node<'a>) {
+    match stack.last_mut() {
+        Some(&mut (_, ref mut children)) => children.push(node),
+        None => {
+            if let Node::Block(block) = node {
+                blocks.push(block);
+            }
+        }
+    }
+}
+
+fn build_node(tag: Tag, children: Vec<Node>) -> Node {
+    match tag {
+        Tag::BlockQuotation(attributes) => Node::Block(Block::BlockQuotation { attributes, elements: blocks(children) }),
+        Tag::CodeBlock(attributes) => {
+            let mut texts = texts(children);
+            let code = if texts.len() == 1 { texts.remove(0) } else { texts.concat().into() };
+            Node::Block(Block::CodeBlock { attributes, code })
+        }
+        Tag::Comment => Node::Block(Block::Comment(lines(children))),
+        Tag::DefinitionList(attributes) => {
+            let elements = children.into_iter()
+                .filter_map(|node| match node {
+                    Node::DefinitionListElement(element) => Some(element),
+                    _ => None,
+                })
+                .collect();
+            Node::Block(Block::DefinitionList { attributes, elements })
+        }
+        Tag::Definition(attributes) => {
+            Node::DefinitionListElement(DefinitionListElement::Definition { attributes, elements: inlines(children) })
+        }
+        Tag::DefinitionTerm(attributes) => {
+            Node::DefinitionListElement(DefinitionListElement::Term { attributes, elements: inlines(children) })
+        }
+        Tag::Div(attributes) => Node::Block(Block::Div { attributes, elements: blocks(children) }),
+        Tag::Figure(attributes) => {
+            let mut caption = inlines(children);
+            let image = if caption.is_empty() {
+                Inline::Image { attributes: Attributes::new(), href: String::new() }
+            } else {
+                caption.remove(0)
+            };
+            Node::Block(Block::Figure { attributes, caption, image: Box::new(image) })
+        }
+        Tag::Footnote { attributes, backlink, id } => {
+            Node::Block(Block::Footnote { attributes, backlink, elements: inlines(children), id })
+        }
+        Tag::Heading { attributes, level } => Node::Block(Block::Heading { attributes, elements: inlines(children), level }),
+        Tag::Html => Node::Block(Block::Html(lines(children))),
+        Tag::Note { attributes, label } => Node::Block(Block::Note { attributes, elements: inlines(children), label }),
+        Tag::NoteList { attributes, backlinks, symbol, unreferenced } => {
+            let elements = children.into_iter()
+                .filter_map(|node| match node {
+                    Node::Note(note) => Some(note),
+                    _ => None,
+                })
+                .collect();
+            Node::Block(Block::NoteList { attributes, backlinks, elements, symbol, unreferenced })
+        }
+        Tag::NoteListItem { attributes, label, number, references } => {
+            Node::Note(Note { attributes, elements: inlines(children), label, number, references })
+        }
+        Tag::NoTextile => Node::Block(Block::NoTextileBlock(lines(children))),
+        Tag::OrderedList { attributes, level } => {
+            Node::Block(Block::OrderedList { attributes, elements: list_elements(children), level })
+        }
+        Tag::ListItem(attributes) => Node::ListElement(ListElement::ListItem { attributes, elements: inlines(children) }),
+        Tag::Paragraph { attributes, starts_with_p } => {
+            Node::Block(Block::Paragraph { attributes, elements: inlines(children), starts_with_p })
+        }
+        Tag::Pre(attributes) => Node::Block(Block::Pre { attributes, lines: lines(children) }),
+        Tag::Table(attributes) => Node::Block(Block::Table { attributes, elements: table_elements(children) }),
+        Tag::TableCaption(attributes) => {
+            Node::TableElement(TableElement::Caption { attributes, elements: inlines(children) })
+        }
+        Tag::TableColGroup { attributes, cols } => Node::TableElement(TableElement::ColGroup { attributes, cols }),
+        Tag::TableData(attributes) => Node::TableCell(TableCell::Data { attributes, elements: inlines(children) }),
+        Tag::TableHeader(attributes) => Node::TableCell(TableCell::Header { attributes, elements: inlines(children) }),
+        Tag::TableRow(attributes) => {
+            let cells = children.into_iter()
+                .filter_map(|node| match node {
+                    Node::TableCell(cell) => Some(cell),
+                    _ => None,
+                })
+                .collect();
+            Node::TableElement(TableElement::Row { attributes, cells })
+        }
+        Tag::TableSection { attributes, tag_type } => {
+            Node::TableElement(TableElement::Section { attributes, rows: table_elements(children), tag_type })
+        }
+        Tag::UnorderedList { attributes, level } => {
+            Node::Block(Block::UnorderedList { attributes, elements: list_elements(children), level })
+        }
+        Tag::Bold { attributes, tag_type } => Node::Inline(Inline::Bold { attributes, elements: inlines(children), tag_type }),
+        Tag::Citation(attributes) => Node::Inline(Inline::Citation { attributes, elements: inlines(children) }),
+        Tag::Italic { attributes, tag_type } => {
+            Node::Inline(Inline::Italic { attributes, elements: inlines(children), tag_type })
+        }
+        Tag::Link(attributes) => Node::Inline(Inline::Link { attributes, elements: inlines(children) }),
+        Tag::Span(attributes) => Node::Inline(Inline::Span { attributes, elements: inlines(children) }),
+        Tag::Strikethrough(attributes) => Node::Inline(Inline::Strikethrough { attributes, elements: inlines(children) }),
+        Tag::Subscript(attributes) => Node::Inline(Inline::Subscript { attributes, elements: inlines(children) }),
+        Tag::Superscript(attributes) => Node::Inline(Inline::Superscript { attributes, elements: inlines(children) }),
+        Tag::Underlined(attributes) => Node::Inline(Inline::Underlined { attributes, elements: inlines(children) }),
+    }
+}
+
+fn blocks(children: Vec<Node>) -> BlockElements {
+    children.into_iter()
+        .filter_map(|node| match node {
+            Node::Block(block) => Some(block),
+            _ => None,
+        })
+        .collect()
+}
+
+fn inlines(children: Vec<Node>) -> Vec<Inline> {
+    children.into_iter()
+        .filter_map(|node| match node {
+            Node::Inline(element) => Some(element),
+            _ => None,
+        })
+        .collect()
+}
+
+fn texts(children: Vec<Node>) -> Vec<Cow<str>> {
+    children.into_iter()
+        .filter_map(|node| match node {
+            Node::Inline(Inline::Text(text)) => Some(text),
+            _ => None,
+        })
+        .collect()
+}
+
+fn lines(children: Vec<Node>) -> Vec<String> {
+    texts(children).into_iter().map(Cow::into_owned).collect()
+}
+
+fn list_elements(children: Vec<Node>) -> Vec<ListElement> {
+    children.into_iter()
+        .filter_map(|node| match node {
+            Node::ListElement(element) => Some(element),
+            Node::Block(list @ Block::OrderedList { .. }) |
+            Node::Block(list @ Block::UnorderedList { .. }) => Some(ListElement::List(list)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn table_elements(children: Vec<Node>) -> Vec<TableElement> {
+    children.into_iter()
+        .filter_map(|node| match node {
+            Node::TableElement(element) => Some(element),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{Attributes, Inline, parse};
+    use super::*;
+
+    #[test]
+    fn yields_events_correctly() {
+        assert_eq!(
+            Parser::new("h1. Heading\n\nSome *bold* text").collect::<Vec<Event>>(),
+            vec![
+                Event::Start(Tag::Heading { attributes: Attributes::new(), level: 1 }),
+                Event::Text("Heading".into()),
+                Event::End(Tag::Heading { attributes: Attributes::new(), level: 1 }),
+                Event::Start(Tag::Paragraph { attributes: Attributes::new(), starts_with_p: false }),
+                Event::Text("Some ".into()),
+                Event::Start(Tag::Bold { attributes: Attributes::new(), tag_type: "strong".to_string() }),
+                Event::Text("bold".into()),
+                Event::End(Tag::Bold { attributes: Attributes::new(), tag_type: "strong".to_string() }),
+                Event::Text(" text".into()),
+                Event::End(Tag::Paragraph { attributes: Attributes::new(), starts_with_p: false }),
+            ]
+        );
+        assert_eq!(
+            Parser::new("bc. code\n\n@x@").collect::<Vec<Event>>(),
+            vec![
+                Event::Start(Tag::CodeBlock(Attributes::new())),
+                Event::Text("code".into()),
+                Event::End(Tag::CodeBlock(Attributes::new())),
+                Event::Start(Tag::Paragraph { attributes: Attributes::new(), starts_with_p: false }),
+                Event::Inline(Inline::Code("x".into())),
+                Event::End(Tag::Paragraph { attributes: Attributes::new(), starts_with_p: false }),
+            ]
+        );
+    }
+
+    #[test]
+    fn converts_events_to_blocks_correctly() {
+        let text = "h1(title). Heading\n\nbq.. Quote\n\nwith two paragraphs\n\np. End\n\n\
+                    # One\n## Two\n* Three\n\n- Term := Definition\n\n\
+                    table(grid).\n|=. Caption\n|:\\2 100 |\n|^.\n|_. A |_. B |\n|-.\n| [#n] | \"Link\":alias |\n\n\
+                    fig. !image.png(Alt)! Caption\n\ndiv.. pre. Pre\n\n###. Comment\n\n\
+                    note#n. Note\n\nnotelist.\n\n[alias]http://example.com";
+        let blocks = parse(text);
+
+        assert_eq!(from_events(Parser::new(text)), blocks);
+        assert_eq!(Parser::new(text).collect::<Vec<Event>>(), Parser::from_blocks(blocks).collect::<Vec<Event>>());
+        assert_eq!(
+            from_events(Parser::new("Some text").chain(vec![Event::End(Tag::Div(Attributes::new()))])),
+            parse("Some text")
+        );
+        assert_eq!(
+            from_events(Parser::new("Some text").take(2)),
+            parse("Some text")
+        );
+    }
+}
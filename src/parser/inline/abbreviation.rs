@@ -1,10 +1,10 @@
 use parser::{Attributes, Inline};
 use parser::patterns::ABBREVIATION_PATTERN;
+use std::borrow::Cow;
 
-pub fn parse_abbreviation(text: &str) -> Option<(Inline, usize)> {
-    if ABBREVIATION_PATTERN.is_match(text) {
-        let caps = ABBREVIATION_PATTERN.captures(text).unwrap();
-        let abbreviation = caps.name("abbreviation").unwrap().to_string();
+pub fn parse_abbreviation(text: &str) -> Option<(Inline<'_>, usize)> {
+    if let Some(caps) = ABBREVIATION_PATTERN.captures(text) {
+        let abbreviation = caps.name("abbreviation").unwrap_or("");
         let transcript = caps.name("transcript").unwrap_or("").to_string();
 
         if transcript.is_empty() {
@@ -12,18 +12,18 @@ pub fn parse_abbreviation(text: &str) -> Option<(Inline, usize)> {
                 Inline::Span {
                     attributes: Attributes::new(),
                     elements: vec![
-                        Inline::Text(abbreviation),
+                        Inline::Text(Cow::Borrowed(abbreviation)),
                     ],
                 },
-                caps.at(0).unwrap().len()
+                caps.at(0).unwrap_or("").len()
             ))
         } else {
             Some((
                 Inline::Abbreviation {
-                    abbr: abbreviation,
+                    abbr: abbreviation.to_string(),
                     transcript: transcript,
                 },
-                caps.at(0).unwrap().len()
+                caps.at(0).unwrap_or("").len()
             ))
         }
     } else {
@@ -31,6 +31,11 @@ pub fn parse_abbreviation(text: &str) -> Option<(Inline, usize)> {
     }
 }
 
+/// Checks that text is abbreviation without transcript, e.g. `ABBR`, which is parsed into span.
+pub fn is_abbreviation(text: &str) -> bool {
+    ABBREVIATION_PATTERN.find(text) == Some((0, text.len()))
+}
+
 #[cfg(test)]
 mod tests {
     use parser::{Attributes, Inline};
@@ -58,7 +63,7 @@ mod tests {
                 Inline::Span {
                     attributes: Attributes::new(),
                     elements: vec![
-                        Inline::Text("ABBR".to_string()),
+                        Inline::Text("ABBR".into()),
                     ],
                 },
                 4
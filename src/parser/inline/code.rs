@@ -1,12 +1,12 @@
 use parser::Inline;
 use parser::patterns::CODE_PATTERN;
+use std::borrow::Cow;
 
-pub fn parse_code(text: &str) -> Option<(Inline, usize)> {
-    if CODE_PATTERN.is_match(text) {
-        let caps = CODE_PATTERN.captures(text).unwrap();
-        let code = caps.name("code").unwrap();
+pub fn parse_code(text: &str) -> Option<(Inline<'_>, usize)> {
+    if let Some(caps) = CODE_PATTERN.captures(text) {
+        let code = caps.name("code").unwrap_or("");
 
-        Some((Inline::Code(code.to_string()), caps.at(0).unwrap().len()))
+        Some((Inline::Code(Cow::Borrowed(code)), caps.at(0).unwrap_or("").len()))
     } else {
         None
     }
@@ -21,11 +21,11 @@ mod tests {
     fn parses_code_correctly() {
         assert_eq!(
             parse_code("@print('Hello World')@"),
-            Some((Inline::Code("print('Hello World')".to_string()), 22))
+            Some((Inline::Code("print('Hello World')".into()), 22))
         );
         assert_eq!(
             parse_code("@@"),
-            Some((Inline::Code("".to_string()), 2))
+            Some((Inline::Code("".into()), 2))
         );
     }
 }
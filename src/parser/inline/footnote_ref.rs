@@ -0,0 +1,49 @@
+use parser::Inline;
+use parser::patterns::FOOTNOTE_REF_PATTERN;
+
+pub fn parse_footnote_ref(text: &str) -> Option<(Inline<'_>, usize)> {
+    FOOTNOTE_REF_PATTERN.captures(text).map(|caps| {
+        (
+            Inline::FootnoteRef {
+                id: caps.name("id").unwrap_or("").to_string(),
+                link: caps.name("nolink").is_none(),
+            },
+            caps.at(0).unwrap_or("").len()
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::Inline;
+    use super::*;
+
+    #[test]
+    fn parses_footnote_ref_correctly() {
+        assert_eq!(
+            parse_footnote_ref("[1]"),
+            Some((
+                Inline::FootnoteRef {
+                    id: "1".to_string(),
+                    link: true,
+                },
+                3
+            ))
+        );
+        assert_eq!(
+            parse_footnote_ref("[10]!"),
+            Some((
+                Inline::FootnoteRef {
+                    id: "10".to_string(),
+                    link: false,
+                },
+                5
+            ))
+        );
+    }
+
+    #[test]
+    fn not_parse_footnote_ref_without_number() {
+        assert_eq!(parse_footnote_ref("[a]"), None);
+    }
+}
@@ -0,0 +1,34 @@
+use parser::Inline;
+use parser::patterns::HTML_INLINE_PATTERN;
+use std::borrow::Cow;
+
+pub fn parse_html(text: &str) -> Option<(Inline<'_>, usize)> {
+    HTML_INLINE_PATTERN.find(text).map(|(_, end)| (Inline::Html(Cow::Borrowed(&text[..end])), end))
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::Inline;
+    use super::*;
+
+    #[test]
+    fn parses_inline_html_correctly() {
+        assert_eq!(
+            parse_html("<span class=\"x-y-z\">Text</span>"),
+            Some((Inline::Html("<span class=\"x-y-z\">".into()), 20))
+        );
+        assert_eq!(
+            parse_html("</kbd>"),
+            Some((Inline::Html("</kbd>".into()), 6))
+        );
+        assert_eq!(
+            parse_html("<!-- comment -->"),
+            Some((Inline::Html("<!-- comment -->".into()), 16))
+        );
+    }
+
+    #[test]
+    fn not_parse_less_than_sign_as_html() {
+        assert_eq!(parse_html("< 5 and > 3"), None);
+    }
+}
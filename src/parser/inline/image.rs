@@ -1,28 +1,37 @@
 use parser::Inline;
 use parser::attributes::parse_inline_attributes;
-use parser::patterns::{IMAGE_PATTERN, IMAGE_ALT_PATTERN};
+use parser::patterns::{IMAGE_PATTERN, IMAGE_ALT_PATTERN, IMAGE_SIZE_PATTERN};
 
-pub fn parse_image(text: &str) -> Option<(Inline, usize)> {
-    if IMAGE_PATTERN.is_match(text) {
-        let caps = IMAGE_PATTERN.captures(text).unwrap();
-        let group_0_len = caps.at(0).unwrap().len();
-        let align = match caps.name("align").unwrap() {
+pub fn parse_image(text: &str) -> Option<(Inline<'_>, usize)> {
+    if let Some(caps) = IMAGE_PATTERN.captures(text) {
+        let group_0_len = caps.at(0).unwrap_or("").len();
+        let align = match caps.name("align").unwrap_or("") {
             "<" => "left",
             "=" => "center",
             ">" => "right",
             _ => "",
         }.to_string();
         let href = caps.name("href").unwrap_or("").to_string();
-        let (mut attrs, string) = parse_inline_attributes(caps.name("string").unwrap());
-        let image_alt_caps = IMAGE_ALT_PATTERN.captures(&*string).unwrap();
-        let src = IMAGE_ALT_PATTERN.replace(&*string, "");
+        let (mut attrs, string) = parse_inline_attributes(caps.name("string").unwrap_or(""));
+        let alt = IMAGE_ALT_PATTERN.captures(string).and_then(|caps| caps.at(1));
+        let mut src = IMAGE_ALT_PATTERN.replace(string, "");
+
+        let size = IMAGE_SIZE_PATTERN.captures(&src).map(|caps| {
+            (caps.name("width").unwrap_or("").to_string(), caps.name("height").unwrap_or("").to_string(), caps.at(0).unwrap_or("").len())
+        });
+
+        if let Some((width, height, len)) = size {
+            attrs.insert("width".to_string(), width);
+            attrs.insert("height".to_string(), height);
+            src.truncate(src.len() - len);
+        }
         attrs.insert("src".to_string(), src);
 
         if !align.is_empty() {
             attrs.insert("align".to_string(), align);
         }
 
-        if let Some(alt) = image_alt_caps.at(1) {
+        if let Some(alt) = alt {
             attrs.insert("alt".to_string(), alt.to_string());
             attrs.insert("title".to_string(), alt.to_string());
         }
@@ -97,6 +106,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parses_image_with_size_correctly() {
+        assert_eq!(
+            parse_image("!image.png 100x50(Image)!"),
+            Some((
+                Inline::Image {
+                    attributes: hashmap!{
+                        "alt".to_string() => "Image".to_string(),
+                        "height".to_string() => "50".to_string(),
+                        "src".to_string() => "image.png".to_string(),
+                        "title".to_string() => "Image".to_string(),
+                        "width".to_string() => "100".to_string(),
+                    },
+                    href: "".to_string(),
+                },
+                25
+            ))
+        );
+    }
+
     #[test]
     fn parses_image_with_link_correctly() {
         assert_eq!(
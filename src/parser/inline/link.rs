@@ -2,41 +2,25 @@ use parser::Inline;
 use parser::attributes::parse_inline_attributes;
 use parser::inline::parse_inline_elements;
 use parser::patterns::{LINK_PATTERN, LINK_TITLE_PATTERN};
+use std::borrow::Cow;
 use url::{Origin, Url};
 
-pub fn parse_link(text: &str) -> Option<(Inline, usize)> {
-    if LINK_PATTERN.is_match(text) {
-        let caps = LINK_PATTERN.captures(text).unwrap();
-        let (mut attrs, mut string) = parse_inline_attributes(caps.name("string").unwrap());
-        let href = caps.name("href").unwrap().to_string();
+pub fn parse_link(text: &str) -> Option<(Inline<'_>, usize)> {
+    if let Some(caps) = LINK_PATTERN.captures(text) {
+        let (mut attrs, mut string) = parse_inline_attributes(caps.name("string").unwrap_or(""));
+        let href = caps.name("href").unwrap_or("").to_string();
 
-        if let Some(title) = LINK_TITLE_PATTERN.captures(&*string).unwrap().at(1) {
-            attrs.insert("title".to_string(), title.to_string());
+        if let Some(caps) = LINK_TITLE_PATTERN.captures(string) {
+            if let Some(title) = caps.at(1) {
+                attrs.insert("title".to_string(), title.to_string());
+            }
+            string = &string[..string.len() - caps.at(0).unwrap_or("").len()];
         }
-        
-        string = LINK_TITLE_PATTERN.replace(&*string, "");
+
         let elements = if string != "$" {
-            parse_inline_elements(&[&*string])
+            parse_inline_elements(&[string])
         } else {
-            let desc = match Url::parse(&*href) {
-                Ok(url) => {
-                    match url.origin() {
-                        Origin::Tuple(..) => {
-                            let path = url.path();
-                            let domain = url.domain().unwrap().to_string();
-
-                            if path != "/" {
-                                domain + path
-                            } else {
-                                domain
-                            }
-                        }
-                        Origin::Opaque(_) => href.replace(&format!("{}:", url.scheme()), "")
-                    }
-                }
-                Err(_) => href.to_string()
-            };
-            vec![Inline::Text(desc)]
+            vec![Inline::Text(Cow::Owned(describe_url(&href)))]
         };
         attrs.insert("href".to_string(), href);
 
@@ -45,13 +29,36 @@ pub fn parse_link(text: &str) -> Option<(Inline, usize)> {
                 attributes: attrs,
                 elements: elements,
             },
-            caps.at(0).unwrap().len()
+            caps.at(0).unwrap_or("").len()
         ))
     } else {
         None
     }
 }
 
+/// Returns text of link with `$` instead of text, e.g. `example.com/page` for `"$":https://example.com/page`.
+pub fn describe_url(href: &str) -> String {
+    match Url::parse(href) {
+        Ok(url) => {
+            match url.origin() {
+                Origin::Tuple(..) => {
+                    let path = url.path();
+                    // Host is IP address, if URL doesn't have domain.
+                    let domain = url.host_str().unwrap_or_default().to_string();
+
+                    if path != "/" {
+                        domain + path
+                    } else {
+                        domain
+                    }
+                }
+                Origin::Opaque(_) => href.replace(&format!("{}:", url.scheme()), "")
+            }
+        }
+        Err(_) => href.to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use parser::{Attributes, Inline};
@@ -70,7 +77,7 @@ mod tests {
                         Inline::Italic {
                             attributes: Attributes::new(),
                             elements: vec![
-                                Inline::Text("Text".to_string()),
+                                Inline::Text("Text".into()),
                             ],
                             tag_type: "em".to_string(),
                         }
@@ -92,7 +99,7 @@ mod tests {
                         "title".to_string() => "With title".to_string(),
                     },
                     elements: vec![
-                        Inline::Text("Link".to_string()),
+                        Inline::Text("Link".into()),
                     ],
                 },
                 37
@@ -110,7 +117,7 @@ mod tests {
                         "href".to_string() => "http://example.com".to_string(),
                     },
                     elements: vec![
-                        Inline::Text("example.com".to_string()),
+                        Inline::Text("example.com".into()),
                     ],
                 },
                 22
@@ -124,11 +131,25 @@ mod tests {
                         "href".to_string() => "mailto:user@example.com".to_string(),
                     },
                     elements: vec![
-                        Inline::Text("user@example.com".to_string()),
+                        Inline::Text("user@example.com".into()),
                     ],
                 },
                 27
             ))
         );
+        assert_eq!(
+            parse_link("\"$\":http://127.0.0.1/path"),
+            Some((
+                Inline::Link {
+                    attributes: hashmap!{
+                        "href".to_string() => "http://127.0.0.1/path".to_string(),
+                    },
+                    elements: vec![
+                        Inline::Text("127.0.0.1/path".into()),
+                    ],
+                },
+                25
+            ))
+        );
     }
 }
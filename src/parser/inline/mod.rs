@@ -1,91 +1,64 @@
 mod abbreviation;
-mod bold;
-mod citation;
 mod code;
+mod footnote_ref;
+mod html;
 mod image;
-mod italic;
 mod link;
 mod no_textile;
-mod span;
-mod strikethrough;
-mod subscript;
-mod superscript;
-mod underlined;
+mod note_ref;
+mod phrase;
+mod scanner;
 
 use parser::{InlineElements, Inline};
-use self::abbreviation::parse_abbreviation;
-use self::bold::parse_bold_text;
-use self::citation::parse_citation;
-use self::code::parse_code;
-use self::image::parse_image;
-use self::italic::parse_italic_text;
-use self::link::parse_link;
-use self::no_textile::parse_no_textile;
-use self::span::parse_span;
-use self::strikethrough::parse_strikethrough_text;
-use self::subscript::parse_subscript_text;
-use self::superscript::parse_superscript_text;
-use self::underlined::parse_underlined_text;
+use parser::location;
+pub use self::abbreviation::is_abbreviation;
+pub use self::link::describe_url;
+use self::scanner::Scanner;
 
-pub fn parse_inline_elements(lines: &[&str]) -> InlineElements {
+pub fn parse_inline_elements<'a>(lines: &[&'a str]) -> InlineElements<'a> {
     let mut tokens = InlineElements::new();
 
-    for (idx, line) in lines.iter().enumerate() {
-        let mut tmp = String::new();
-        let mut cur_byte = 0;
-        let mut it = line.char_indices();
+    for (idx, &line) in lines.iter().enumerate() {
+        tokens.extend(Scanner::new(line).parse());
 
-        while cur_byte < line.len() {
-            match parse_inline_element(&line[cur_byte..line.len()]) {
-                Some((span, consumed_bytes)) => {
-                    if !tmp.is_empty() {
-                        tokens.push(Inline::Text(tmp));
-                    }
-                    tokens.push(span);
-                    tmp = String::new();
-                    cur_byte += consumed_bytes;
-                }
-                None => {
-                    let (_, ch) = it.find(|el| el.0 == cur_byte).unwrap();
-                    tmp.push(ch);
-                    cur_byte += ch.len_utf8();
-                }
-            }
-        }
-        if !tmp.is_empty() {
-            tokens.push(Inline::Text(tmp.clone()));
-        }
         if idx < lines.len() - 1 && !lines[idx + 1].starts_with(" ") {
             tokens.push(Inline::Break);
+            // Line break spans from the end of line to the start of next line.
+            let range = location::locate(line).and_then(|(_, end)| {
+                location::locate(lines[idx + 1]).map(|(start, _)| (end, start.max(end)))
+            });
+            location::push(location::mark(), range);
         }
     }
     tokens
 }
 
-fn parse_inline_element(text: &str) -> Option<(Inline, usize)> {
-    lazy_static! {
-        static ref FNS: Vec<fn(&str) -> Option<(Inline, usize)>> = vec![
-            parse_abbreviation,
-            parse_bold_text,
-            parse_citation,
-            parse_code,
-            parse_image,
-            parse_italic_text,
-            parse_link,
-            parse_no_textile,
-            parse_span,
-            parse_strikethrough_text,
-            parse_subscript_text,
-            parse_superscript_text,
-            parse_underlined_text,
-        ];
-    }
+#[cfg(test)]
+mod tests {
+    use parser::{Attributes, Inline};
+    use super::*;
 
-    for f in FNS.iter() {
-        let res = f(text);
-        if let Some(_) = res {
-            return res;
-        }
+    #[test]
+    fn not_parse_phrases_inside_words() {
+        assert_eq!(
+            parse_inline_elements(&["foo-bar-baz, snake_case_name and 1990 - 2000 - now"]),
+            vec![
+                Inline::Text("foo-bar-baz, snake_case_name and 1990 - 2000 - now".into()),
+            ]
+        );
+        assert_eq!(
+            parse_inline_elements(&["(*bold*) _not_italic"]),
+            vec![
+                Inline::Text("(".into()),
+                Inline::Bold {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        Inline::Text("bold".into()),
+                    ],
+                    tag_type: "strong".to_string(),
+                },
+                Inline::Text(") _not_italic".into()),
+            ]
+        );
     }
-    None
 }
@@ -1,13 +1,13 @@
 use parser::Inline;
 use parser::patterns::NO_TEXTILE_INLINE_PATTERN;
+use std::borrow::Cow;
 
-pub fn parse_no_textile(text: &str) -> Option<(Inline, usize)> {
-    if NO_TEXTILE_INLINE_PATTERN.is_match(text) {
-        let caps = NO_TEXTILE_INLINE_PATTERN.captures(text).unwrap();
-        let group_0 = caps.at(0).unwrap();
-        let string = caps.name("string").unwrap().to_string();
+pub fn parse_no_textile(text: &str) -> Option<(Inline<'_>, usize)> {
+    if let Some(caps) = NO_TEXTILE_INLINE_PATTERN.captures(text) {
+        let group_0 = caps.at(0).unwrap_or("");
+        let string = caps.name("string").unwrap_or("");
 
-        Some((Inline::Text(string), group_0.len()))
+        Some((Inline::NoTextile(Cow::Borrowed(string)), group_0.len()))
     } else {
         None
     }
@@ -23,14 +23,14 @@ mod tests {
         assert_eq!(
             parse_no_textile("==^Disabled Textile formatting^=="),
             Some((
-                Inline::Text("^Disabled Textile formatting^".to_string()),
+                Inline::NoTextile("^Disabled Textile formatting^".into()),
                 33
             ))
         );
         assert_eq!(
             parse_no_textile("===="),
             Some((
-                Inline::Text("".to_string()),
+                Inline::NoTextile("".into()),
                 4
             ))
         );
@@ -0,0 +1,50 @@
+use parser::Inline;
+use parser::patterns::NOTE_REF_PATTERN;
+
+pub fn parse_note_ref(text: &str) -> Option<(Inline<'_>, usize)> {
+    NOTE_REF_PATTERN.captures(text).map(|caps| {
+        (
+            Inline::NoteRef {
+                label: caps.name("label").unwrap_or("").to_string(),
+                link: caps.name("nolink").is_none(),
+                number: 0,
+                reference: 0,
+            },
+            caps.at(0).unwrap_or("").len()
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::Inline;
+    use super::*;
+
+    #[test]
+    fn parses_note_ref_correctly() {
+        assert_eq!(
+            parse_note_ref("[#intro]"),
+            Some((
+                Inline::NoteRef {
+                    label: "intro".to_string(),
+                    link: true,
+                    number: 0,
+                    reference: 0,
+                },
+                8
+            ))
+        );
+        assert_eq!(
+            parse_note_ref("[#intro!]"),
+            Some((
+                Inline::NoteRef {
+                    label: "intro".to_string(),
+                    link: false,
+                    number: 0,
+                    reference: 0,
+                },
+                9
+            ))
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use parser::{Attributes, Inline, InlineElements};
+
+/// Phrase with children, which is delimited by the same characters on both sides, e.g. `*bold*` or `??citation??`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Phrase {
+    Bold(&'static str),
+    Citation,
+    Italic(&'static str),
+    Span,
+    Strikethrough,
+    Subscript,
+    Superscript,
+    Underlined,
+}
+
+impl Phrase {
+    /// Returns phrase delimited by `count` characters `delimiter`, e.g. `Bold("b")` for `**`.
+    /// Phrases with other counts, e.g. `***text***`, are rendered as text.
+    pub fn from_delimiter(delimiter: char, count: usize) -> Option<Phrase> {
+        match (delimiter, count) {
+            ('*', 1) => Some(Phrase::Bold("strong")),
+            ('*', 2) => Some(Phrase::Bold("b")),
+            ('?', 2) => Some(Phrase::Citation),
+            ('_', 1) => Some(Phrase::Italic("em")),
+            ('_', 2) => Some(Phrase::Italic("i")),
+            ('%', 1) => Some(Phrase::Span),
+            ('-', 1) => Some(Phrase::Strikethrough),
+            ('~', 1) => Some(Phrase::Subscript),
+            ('^', 1) => Some(Phrase::Superscript),
+            ('+', 1) => Some(Phrase::Underlined),
+            _ => None,
+        }
+    }
+
+    /// Builds element of phrase with its children.
+    pub fn into_element(self, attributes: Attributes, elements: InlineElements) -> Inline {
+        match self {
+            Phrase::Bold(tag_type) => Inline::Bold { attributes, elements, tag_type: tag_type.to_string() },
+            Phrase::Citation => Inline::Citation { attributes, elements },
+            Phrase::Italic(tag_type) => Inline::Italic { attributes, elements, tag_type: tag_type.to_string() },
+            Phrase::Span => Inline::Span { attributes, elements },
+            Phrase::Strikethrough => Inline::Strikethrough { attributes, elements },
+            Phrase::Subscript => Inline::Subscript { attributes, elements },
+            Phrase::Superscript => Inline::Superscript { attributes, elements },
+            Phrase::Underlined => Inline::Underlined { attributes, elements },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{Attributes, Inline};
+    use parser::inline::parse_inline_elements;
+
+    #[test]
+    fn parses_bold_text_correctly() {
+        assert_eq!(
+            parse_inline_elements(&["*Strong text*"]),
+            vec![
+                Inline::Bold {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        Inline::Text("Strong text".into()),
+                    ],
+                    tag_type: "strong".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            parse_inline_elements(&["**Bold text**"]),
+            vec![
+                Inline::Bold {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        Inline::Text("Bold text".into()),
+                    ],
+                    tag_type: "b".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_bold_text_with_mismatched_delimiters_as_text() {
+        assert_eq!(
+            parse_inline_elements(&["**Text*"]),
+            vec![
+                Inline::Text("**Text*".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_citation_correctly() {
+        assert_eq!(
+            parse_inline_elements(&["??Textile citation element??"]),
+            vec![
+                Inline::Citation {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        Inline::Text("Textile citation element".into()),
+                    ],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_italic_text_correctly() {
+        assert_eq!(
+            parse_inline_elements(&["_Emphasis text_"]),
+            vec![
+                Inline::Italic {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        Inline::Text("Emphasis text".into())
+                    ],
+                    tag_type: "em".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            parse_inline_elements(&["__Italic text__"]),
+            vec![
+                Inline::Italic {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        Inline::Text("Italic text".into())
+                    ],
+                    tag_type: "i".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_span_correctly() {
+        assert_eq!(
+            parse_inline_elements(&["%Span element%"]),
+            vec![
+                Inline::Span {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        Inline::Text("Span element".into()),
+                    ],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_strikethrough_text_correctly() {
+        assert_eq!(
+            parse_inline_elements(&["-Strikethrough text-"]),
+            vec![
+                Inline::Strikethrough {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        Inline::Text("Strikethrough text".into()),
+                    ],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_subscript_text_correctly() {
+        assert_eq!(
+            parse_inline_elements(&["~Subscript text~"]),
+            vec![
+                Inline::Subscript {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        Inline::Text("Subscript text".into()),
+                    ],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_superscript_text_correctly() {
+        assert_eq!(
+            parse_inline_elements(&["^Superscript text^"]),
+            vec![
+                Inline::Superscript {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        Inline::Text("Superscript text".into()),
+                    ],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_underlined_text_correctly() {
+        assert_eq!(
+            parse_inline_elements(&["+Underlined text+"]),
+            vec![
+                Inline::Underlined {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        Inline::Text("Underlined text".into()),
+                    ],
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,680 @@
+//! Scanner of inline elements, which walks every line once.
+//!
+//! Positions of delimiters are indexed before scanning, so closing delimiter of phrase, e.g. `*` of bold text,
+//! is found by binary search instead of matching text after every character. Phrases, which are being scanned,
+//! are kept in stack, and their children are scanned in the same pass.
+
+use parser::{Attributes, Inline, InlineElements};
+use parser::attributes::parse_inline_attributes;
+use parser::diagnostic::Severity;
+use parser::inline::abbreviation::parse_abbreviation;
+use parser::inline::code::parse_code;
+use parser::inline::footnote_ref::parse_footnote_ref;
+use parser::inline::html::parse_html;
+use parser::inline::image::parse_image;
+use parser::inline::link::parse_link;
+use parser::inline::no_textile::parse_no_textile;
+use parser::inline::note_ref::parse_note_ref;
+use parser::inline::phrase::Phrase;
+use parser::location::{self, Mark};
+use parser::patterns::{ABBREVIATION_PATTERN, FOOTNOTE_REF_PATTERN, NOTE_REF_PATTERN};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// Characters, which positions are indexed.
+const DELIMITERS: &[u8] = b"!%()*+-<>?@^_~";
+
+/// Parser of element without children, which is given the exact text of element.
+type LeafParser = fn(&str) -> Option<(Inline<'_>, usize)>;
+
+/// Positions of delimiter character in line with runs of it, e.g. `**`, which they belong to.
+#[derive(Default)]
+struct Runs {
+    positions: Vec<usize>,
+    /// Start and end of run for every position.
+    runs: Vec<(usize, usize)>,
+}
+
+impl Runs {
+    /// Returns the first position in `from..end`.
+    fn find(&self, from: usize, end: usize) -> Option<usize> {
+        find(&self.positions, from, end)
+    }
+
+    /// Returns start and end of run, which contains delimiter at `pos`.
+    fn run(&self, pos: usize) -> (usize, usize) {
+        match self.positions.binary_search(&pos) {
+            Ok(idx) => self.runs[idx],
+            Err(_) => (pos, pos),
+        }
+    }
+}
+
+/// Positions of delimiters in line.
+struct Index {
+    /// Runs of single characters by their ASCII codes.
+    chars: Vec<Runs>,
+    /// Positions of `??`, which may overlap, e.g. in `???`.
+    citations: Vec<usize>,
+    /// Positions of `-->`, which closes HTML comment.
+    comment_ends: Vec<usize>,
+    /// Positions of `":`, which are followed by URL of link.
+    link_separators: Vec<usize>,
+    /// Positions of `==`, which may overlap.
+    no_textile: Vec<usize>,
+    /// Positions of closing square brackets by positions of opening ones.
+    brackets: HashMap<usize, usize>,
+}
+
+impl Index {
+    fn new(line: &str) -> Index {
+        let bytes = line.as_bytes();
+        let mut index = Index {
+            chars: (0..128).map(|_| Runs::default()).collect(),
+            citations: Vec::new(),
+            comment_ends: Vec::new(),
+            link_separators: Vec::new(),
+            no_textile: Vec::new(),
+            brackets: HashMap::new(),
+        };
+        let mut open_brackets = Vec::new();
+
+        for (pos, &byte) in bytes.iter().enumerate() {
+            let rest = &bytes[pos..];
+
+            match byte {
+                b'[' => open_brackets.push(pos),
+                b']' => {
+                    if let Some(start) = open_brackets.pop() {
+                        index.brackets.insert(start, pos);
+                    }
+                }
+                // URL of link can't be empty or start with space or parenthesis.
+                b'"' if rest.starts_with(b"\":") &&
+                    line[pos + 2..].chars().next().is_some_and(|ch| !matches!(ch, ' ' | '(' | ')')) => {
+                    index.link_separators.push(pos);
+                }
+                b'?' if rest.starts_with(b"??") => index.citations.push(pos),
+                b'-' if rest.starts_with(b"-->") => index.comment_ends.push(pos),
+                b'=' if rest.starts_with(b"==") => index.no_textile.push(pos),
+                _ => {}
+            }
+
+            if DELIMITERS.contains(&byte) {
+                let runs = &mut index.chars[byte as usize];
+                let start = match (runs.positions.last(), runs.runs.last()) {
+                    (Some(&last), Some(&(start, _))) if last + 1 == pos => start,
+                    _ => pos,
+                };
+                runs.positions.push(pos);
+                runs.runs.push((start, pos + 1));
+            }
+        }
+
+        // Ends of runs are known only after the whole run is indexed.
+        for runs in &mut index.chars {
+            for idx in (1..runs.positions.len()).rev() {
+                if runs.positions[idx - 1] + 1 == runs.positions[idx] {
+                    runs.runs[idx - 1].1 = runs.runs[idx].1;
+                }
+            }
+        }
+        index
+    }
+
+    fn runs(&self, delimiter: char) -> &Runs {
+        &self.chars[delimiter as usize]
+    }
+}
+
+/// Element found at position in line.
+#[derive(Clone, Copy)]
+struct Found {
+    kind: Kind,
+    /// Range of element text without square brackets, which may enclose it.
+    start: usize,
+    end: usize,
+    /// End of element with closing square bracket.
+    next: usize,
+}
+
+#[derive(Clone, Copy)]
+enum Kind {
+    /// Element without children, e.g. image or link, which is parsed from its text.
+    Leaf(LeafParser),
+    /// Phrase with mismatched delimiters, e.g. `**text*`, with range of string between delimiters.
+    Mismatched(usize, usize),
+    /// Phrase with range of string between delimiters, which children are scanned.
+    Phrase(Phrase, usize, usize),
+}
+
+/// Phrase, which children are being scanned.
+struct Frame<'a> {
+    phrase: Phrase,
+    attributes: Attributes,
+    elements: InlineElements<'a>,
+    /// Mark, after which children of phrase are recorded.
+    mark: Mark,
+    /// Start of phrase, end of its children and end of phrase with closing delimiter.
+    start: usize,
+    end: usize,
+    next: usize,
+}
+
+pub struct Scanner<'a> {
+    line: &'a str,
+    index: Index,
+    /// Elements in square brackets by positions of opening brackets, so nested brackets are scanned once.
+    bracketed: HashMap<usize, Option<Found>>,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(line: &'a str) -> Scanner<'a> {
+        Scanner {
+            line,
+            index: Index::new(line),
+            bracketed: HashMap::new(),
+        }
+    }
+
+    /// Parses inline elements of line.
+    pub fn parse(mut self) -> InlineElements<'a> {
+        let line = self.line;
+        let mut elements = InlineElements::new();
+        let mut stack: Vec<Frame<'a>> = Vec::new();
+        // Text between elements is borrowed from line.
+        let mut text_start = 0;
+        let mut pos = 0;
+        let mut prev = None;
+
+        loop {
+            let end = stack.last().map_or(line.len(), |frame| frame.end);
+
+            if pos >= end {
+                push_text(children(&mut stack, &mut elements), &line[text_start..end]);
+
+                match stack.pop() {
+                    Some(frame) => {
+                        let phrase = &line[frame.start..frame.next];
+                        location::push(frame.mark, location::locate(phrase));
+                        children(&mut stack, &mut elements).push(frame.phrase.into_element(frame.attributes, frame.elements));
+                        prev = phrase.chars().last();
+                        pos = frame.next;
+                        text_start = pos;
+                        continue;
+                    }
+                    None => break,
+                }
+            }
+
+            match self.element_at(pos, prev, end) {
+                Some(found) => {
+                    push_text(children(&mut stack, &mut elements), &line[text_start..pos]);
+                    let mark = location::mark();
+
+                    let element = match found.kind {
+                        Kind::Leaf(parse) => {
+                            let text = &line[found.start..found.end];
+                            parse(text).map_or(Inline::Text(Cow::Borrowed(text)), |(element, _)| element)
+                        }
+                        Kind::Mismatched(string_start, string_end) => {
+                            // Attributes are parsed only to report problems in them.
+                            parse_inline_attributes(&line[string_start..string_end]);
+                            parse_mismatched_phrase(&line[found.start..found.end])
+                        }
+                        Kind::Phrase(phrase, string_start, string_end) => {
+                            let (attributes, string) = parse_inline_attributes(&line[string_start..string_end]);
+
+                            stack.push(Frame {
+                                phrase,
+                                attributes,
+                                elements: InlineElements::new(),
+                                mark,
+                                start: pos,
+                                end: string_end,
+                                next: found.next,
+                            });
+                            pos = string_end - string.len();
+                            text_start = pos;
+                            prev = None;
+                            continue;
+                        }
+                    };
+                    children(&mut stack, &mut elements).push(element);
+                    location::push(mark, location::locate(&line[pos..found.next]));
+                    prev = line[pos..found.next].chars().last();
+                    pos = found.next;
+                    text_start = pos;
+                }
+                None => {
+                    let ch = match line[pos..end].chars().next() {
+                        Some(ch) => ch,
+                        None => break,
+                    };
+
+                    if let Some(delimiter) = get_unclosed_phrase_delimiter(&line[pos..end], prev) {
+                        location::report(Severity::Warning,
+                                         format!("Unclosed `{}` phrase is rendered as text", delimiter),
+                                         delimiter);
+                    }
+                    prev = Some(ch);
+                    pos += ch.len_utf8();
+                }
+            }
+        }
+        elements
+    }
+
+    /// Finds element, which starts at `pos` and ends before `end`. `prev` is the character before element.
+    fn element_at(&mut self, pos: usize, prev: Option<char>, end: usize) -> Option<Found> {
+        let ch = self.line[pos..end].chars().next()?;
+
+        // Phrases are allowed only at word boundaries, so `snake_case_name` isn't parsed as italic text.
+        if prev.is_none_or(|ch| !ch.is_alphanumeric()) {
+            let found = match ch {
+                '*' | '_' | '%' | '-' | '~' | '^' | '+' => self.phrase_at(pos, ch, end),
+                '?' => self.citation_at(pos, end),
+                '@' => self.code_end(pos, end).map(|next| leaf(parse_code, pos, next)),
+                _ => None,
+            };
+
+            if found.is_some() {
+                return found;
+            }
+        }
+
+        match ch {
+            '[' => self.bracketed_at(pos, end),
+            '<' => self.html_end(pos, end).map(|next| leaf(parse_html, pos, next)),
+            '!' => self.image_end(pos, end).map(|next| leaf(parse_image, pos, next)),
+            '"' => self.link_end(pos, end).map(|next| leaf(parse_link, pos, next)),
+            '=' => self.no_textile_end(pos, end).map(|next| leaf(parse_no_textile, pos, next)),
+            ch if ch.is_uppercase() => self.abbreviation_end(pos, end).map(|next| leaf(parse_abbreviation, pos, next)),
+            _ => None,
+        }
+    }
+
+    /// Finds phrase delimited by single characters, e.g. `*bold*` or `__italic__`.
+    fn phrase_at(&self, pos: usize, delimiter: char, end: usize) -> Option<Found> {
+        let line = self.line;
+        let runs = self.index.runs(delimiter);
+        let string_start = runs.run(pos).1.min(end);
+        let first = line[string_start..end].chars().next()?;
+        let string_end = runs.find(string_start + first.len_utf8(), end)?;
+        let phrase_end = runs.run(string_end).1.min(end);
+
+        if !self.is_at_word_boundary(&line[string_start..string_end], phrase_end, end) {
+            return None;
+        }
+
+        let count = string_start - pos;
+        let kind = match Phrase::from_delimiter(delimiter, count) {
+            Some(phrase) if phrase_end - string_end == count => Kind::Phrase(phrase, string_start, string_end),
+            _ => Kind::Mismatched(string_start, string_end),
+        };
+        Some(Found { kind, start: pos, end: phrase_end, next: phrase_end })
+    }
+
+    /// Finds citation, e.g. `??citation??`.
+    fn citation_at(&self, pos: usize, end: usize) -> Option<Found> {
+        let line = self.line;
+
+        if !line[pos..end].starts_with("??") {
+            return None;
+        }
+
+        let string_start = pos + 2;
+        let first = line[string_start..end].chars().next()?;
+        let string_end = find(&self.index.citations, string_start + first.len_utf8(), end - 1)?;
+        let phrase_end = string_end + 2;
+        // Content of phrase is checked without all question marks around it, e.g. in `???text??`.
+        let runs = self.index.runs('?');
+        let content_start = runs.run(pos).1.min(phrase_end);
+        let content_end = runs.run(phrase_end - 1).0.max(content_start);
+
+        if !self.is_at_word_boundary(&line[content_start..content_end], phrase_end, end) {
+            return None;
+        }
+        Some(Found { kind: Kind::Phrase(Phrase::Citation, string_start, string_end), start: pos, end: phrase_end, next: phrase_end })
+    }
+
+    /// Checks that content of phrase isn't padded by spaces inside delimiters and phrase isn't followed by word.
+    fn is_at_word_boundary(&self, content: &str, phrase_end: usize, end: usize) -> bool {
+        !content.is_empty() &&
+        !content.starts_with(char::is_whitespace) &&
+        !content.ends_with(char::is_whitespace) &&
+        self.line[phrase_end..end].chars().next().is_none_or(|ch| !ch.is_alphanumeric())
+    }
+
+    /// Finds end of code, e.g. `@code@`.
+    fn code_end(&self, pos: usize, end: usize) -> Option<usize> {
+        let string_end = self.index.runs('@').find(pos + 1, end)?;
+
+        if self.is_at_word_boundary(&self.line[pos + 1..string_end], string_end + 1, end) {
+            Some(string_end + 1)
+        } else {
+            None
+        }
+    }
+
+    /// Finds element in square brackets, e.g. `[*bold*]text`, or reference to note, e.g. `[1]` or `[#label]`.
+    fn bracketed_at(&mut self, pos: usize, end: usize) -> Option<Found> {
+        if let Some(&close) = self.index.brackets.get(&pos) {
+            if close < end {
+                if let Some(found) = self.bracketed(pos) {
+                    return Some(Found { next: close + 1, ..found });
+                }
+            }
+        }
+
+        let text = &self.line[pos..end];
+        // Reference to footnote is attached to word, e.g. `word[1]`, so `[1]` after space is text.
+        let is_attached = self.line[..pos].chars().next_back().is_some_and(|ch| !ch.is_whitespace());
+
+        FOOTNOTE_REF_PATTERN.find(text).filter(|_| is_attached).map(|(_, len)| leaf(parse_footnote_ref, pos, pos + len))
+            .or_else(|| NOTE_REF_PATTERN.find(text).map(|(_, len)| leaf(parse_note_ref, pos, pos + len)))
+    }
+
+    /// Returns element, which takes the whole text in square brackets at `pos`.
+    /// Unlike other elements, it may be placed inside word.
+    fn bracketed(&mut self, pos: usize) -> Option<Found> {
+        if let Some(&found) = self.bracketed.get(&pos) {
+            return found;
+        }
+
+        // Nested brackets, e.g. `[[*bold*]]`, are resolved from the innermost ones, so scanning isn't recursive.
+        let bytes = self.line.as_bytes();
+        let mut nested = vec![pos];
+        while let Some(&start) = nested.last() {
+            let inner = start + 1;
+            if bytes.get(inner) != Some(&b'[') || !self.index.brackets.contains_key(&inner) ||
+               self.bracketed.contains_key(&inner) {
+                break;
+            }
+            nested.push(inner);
+        }
+
+        while let Some(start) = nested.pop() {
+            let close = self.index.brackets[&start];
+            let found = self.element_at(start + 1, None, close).filter(|found| found.next == close);
+            self.bracketed.insert(start, found);
+        }
+        self.bracketed[&pos]
+    }
+
+    /// Finds end of HTML tag, e.g. `<kbd>`, or comment, e.g. `<!-- text -->`.
+    fn html_end(&self, pos: usize, end: usize) -> Option<usize> {
+        let text = &self.line[pos..end];
+
+        if text.starts_with("<!--") {
+            return find(&self.index.comment_ends, pos + 4, end - 2).map(|comment_end| comment_end + 3);
+        }
+
+        let bytes = text.as_bytes();
+        let mut cur = 1;
+        if bytes.get(cur) == Some(&b'/') {
+            cur += 1;
+        }
+        if !bytes.get(cur).is_some_and(|byte| byte.is_ascii_alphabetic()) {
+            return None;
+        }
+        while bytes.get(cur + 1).is_some_and(|&byte| byte.is_ascii_alphanumeric() || byte == b'-') {
+            cur += 1;
+        }
+        cur += 1;
+
+        let rest = &text[cur..];
+        if rest.starts_with('>') {
+            return Some(pos + cur + 1);
+        }
+        if rest.starts_with("/>") {
+            return Some(pos + cur + 2);
+        }
+
+        // Attributes of tag end at the first angle bracket.
+        let space = rest.chars().next().filter(|ch| ch.is_whitespace())?;
+        let from = pos + cur + space.len_utf8();
+        let close = self.index.runs('>').find(from, end)?;
+
+        match self.index.runs('<').find(from, end) {
+            Some(open) if open < close => None,
+            _ => Some(close + 1),
+        }
+    }
+
+    /// Finds end of image, e.g. `!image.png!` or `!image.png!:http://example.com` with link.
+    fn image_end(&self, pos: usize, end: usize) -> Option<usize> {
+        let line = self.line;
+        let runs = self.index.runs('!');
+        // Alignment before source is optional, so image is searched with and without it.
+        let with_align = match line.as_bytes()[pos + 1..end].first() {
+            Some(b'<' | b'|' | b'>' | b'=') => Some(pos + 2),
+            _ => None,
+        };
+        let string_end = with_align.into_iter()
+            .chain(Some(pos + 1))
+            .filter_map(|string_start| {
+                let first = line[string_start..end].chars().next()?;
+                runs.find(string_start + first.len_utf8(), end)
+            })
+            .next()?;
+        let image_end = string_end + 1;
+
+        // URL of link, which contains image.
+        match line[image_end..end].strip_prefix(':') {
+            Some(rest) => {
+                let href_len = rest.find([' ', '(', ')']).unwrap_or(rest.len());
+                Some(if href_len > 0 { image_end + 1 + href_len } else { image_end })
+            }
+            None => Some(image_end),
+        }
+    }
+
+    /// Finds end of link, e.g. `"Link":http://example.com`.
+    fn link_end(&self, pos: usize, end: usize) -> Option<usize> {
+        let line = self.line;
+        let first = line[pos + 1..end].chars().next()?;
+        let separator = find(&self.index.link_separators, pos + 1 + first.len_utf8(), end)?;
+        let href_start = separator + 2;
+
+        if href_start >= end {
+            return None;
+        }
+        let href_len = line[href_start..end].find([' ', '(', ')']).unwrap_or(end - href_start);
+        Some(href_start + href_len)
+    }
+
+    /// Finds end of text, which isn't formatted, e.g. `==*text*==`.
+    fn no_textile_end(&self, pos: usize, end: usize) -> Option<usize> {
+        if !self.line[pos..end].starts_with("==") {
+            return None;
+        }
+        find(&self.index.no_textile, pos + 2, end - 1).map(|string_end| string_end + 2)
+    }
+
+    /// Finds end of abbreviation, e.g. `ABBR` or `ABBR(Abbreviation)`.
+    fn abbreviation_end(&self, pos: usize, end: usize) -> Option<usize> {
+        // Transcript ends at the first closing parenthesis, so pattern isn't matched against the rest of line.
+        let bound = match self.index.runs(')').find(pos, end) {
+            Some(close) => close + 1,
+            None => self.index.runs('(').find(pos, end).unwrap_or(end),
+        };
+        ABBREVIATION_PATTERN.find(&self.line[pos..bound]).map(|(_, len)| pos + len)
+    }
+}
+
+/// Returns the first position in `from..end` of sorted positions.
+fn find(positions: &[usize], from: usize, end: usize) -> Option<usize> {
+    positions.get(positions.partition_point(|&pos| pos < from)).cloned().filter(|&pos| pos < end)
+}
+
+fn leaf(parse: LeafParser, start: usize, end: usize) -> Found {
+    Found { kind: Kind::Leaf(parse), start, end, next: end }
+}
+
+/// Returns elements of the innermost phrase being scanned or elements of line.
+fn children<'a, 'b>(stack: &'b mut [Frame<'a>], elements: &'b mut InlineElements<'a>) -> &'b mut InlineElements<'a> {
+    match stack.last_mut() {
+        Some(frame) => &mut frame.elements,
+        None => elements,
+    }
+}
+
+fn push_text<'a>(elements: &mut InlineElements<'a>, text: &'a str) {
+    if !text.is_empty() {
+        elements.push(Inline::Text(Cow::Borrowed(text)));
+        location::push(location::mark(), location::locate(text));
+    }
+}
+
+/// Returns phrase with mismatched delimiters, e.g. `**Text*`, as text.
+fn parse_mismatched_phrase(phrase: &str) -> Inline<'_> {
+    location::report(Severity::Warning,
+                     format!("Phrase `{}` with mismatched delimiters is rendered as text", phrase),
+                     phrase);
+    Inline::Text(Cow::Borrowed(phrase))
+}
+
+/// Returns opening delimiter of phrase, e.g. `*` of bold text, if text starts with it.
+/// It is used, if phrase isn't parsed.
+fn get_unclosed_phrase_delimiter(text: &str, prev: Option<char>) -> Option<&str> {
+    let delimiter = match text.chars().next() {
+        Some('?') if text.starts_with("??") => &text[..2],
+        Some(ch @ ('*' | '_' | '+' | '^' | '~' | '%' | '@')) => &text[..ch.len_utf8()],
+        _ => return None,
+    };
+    let is_opening = prev.is_none_or(|ch| !ch.is_alphanumeric()) &&
+                     text[delimiter.len()..].chars().next().is_some_and(|ch| !ch.is_whitespace() && !delimiter.starts_with(ch));
+
+    if is_opening {
+        Some(delimiter)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{Attributes, Inline};
+    use parser::inline::parse_inline_elements;
+
+    #[test]
+    fn parses_bracketed_phrase_correctly() {
+        assert_eq!(
+            parse_inline_elements(&["[*bold*]text"]),
+            vec![
+                Inline::Bold {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        Inline::Text("bold".into()),
+                    ],
+                    tag_type: "strong".to_string(),
+                },
+                Inline::Text("text".into()),
+            ]
+        );
+        assert_eq!(
+            parse_inline_elements(&["[\"Link\":http://example.com]s"]),
+            vec![
+                Inline::Link {
+                    attributes: hashmap!{
+                        "href".to_string() => "http://example.com".to_string(),
+                    },
+                    elements: vec![
+                        Inline::Text("Link".into()),
+                    ],
+                },
+                Inline::Text("s".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn not_parse_brackets_without_phrase() {
+        assert_eq!(
+            parse_inline_elements(&["[*bold* text]"]),
+            vec![
+                Inline::Text("[".into()),
+                Inline::Bold {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        Inline::Text("bold".into()),
+                    ],
+                    tag_type: "strong".to_string(),
+                },
+                Inline::Text(" text]".into()),
+            ]
+        );
+        assert_eq!(
+            parse_inline_elements(&["[*unclosed"]),
+            vec![
+                Inline::Text("[*unclosed".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_nested_phrases_correctly() {
+        assert_eq!(
+            parse_inline_elements(&["*bold _italic_* [[%span%]]"]),
+            vec![
+                Inline::Bold {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        Inline::Text("bold ".into()),
+                        Inline::Italic {
+                            attributes: Attributes::new(),
+                            elements: vec![
+                                Inline::Text("italic".into()),
+                            ],
+                            tag_type: "em".to_string(),
+                        },
+                    ],
+                    tag_type: "strong".to_string(),
+                },
+                Inline::Text(" ".into()),
+                Inline::Span {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        Inline::Text("span".into()),
+                    ],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_footnote_refs_only_after_words() {
+        assert_eq!(
+            parse_inline_elements(&["Text[1] and *bold*[2]!"]),
+            vec![
+                Inline::Text("Text".into()),
+                Inline::FootnoteRef {
+                    id: "1".to_string(),
+                    link: true,
+                },
+                Inline::Text(" and ".into()),
+                Inline::Bold {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        Inline::Text("bold".into()),
+                    ],
+                    tag_type: "strong".to_string(),
+                },
+                Inline::FootnoteRef {
+                    id: "2".to_string(),
+                    link: false,
+                },
+            ]
+        );
+        assert_eq!(parse_inline_elements(&["see [1] here"]), vec![Inline::Text("see [1] here".into())]);
+        assert_eq!(parse_inline_elements(&["[1] at start"]), vec![Inline::Text("[1] at start".into())]);
+    }
+
+    #[test]
+    fn parses_deeply_nested_brackets() {
+        let line = format!("{}x{}", "[".repeat(100_000), "]".repeat(100_000));
+
+        assert_eq!(parse_inline_elements(&[&line]), vec![Inline::Text(line.as_str().into())]);
+    }
+}
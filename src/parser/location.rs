@@ -0,0 +1,258 @@
+//! Source positions of parsed elements.
+//!
+//! Parsers report byte ranges of elements and problems in markup while recording is enabled by `record`.
+//! Ranges are located by addresses of string slices, so only text borrowed from source is tracked.
+
+use parser::diagnostic::{Diagnostic, Severity};
+use std::cell::RefCell;
+
+/// Position in source text.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Position {
+    /// Byte offset from the start of text.
+    pub offset: usize,
+    /// Line number, starting from 1.
+    pub line: usize,
+    /// Column number in characters, starting from 1.
+    pub column: usize,
+}
+
+/// Range of source text, which element is parsed from. `end` points after the last character.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// Span of element with spans of its nested elements in document order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpanNode {
+    pub span: Span,
+    pub children: Vec<SpanNode>,
+}
+
+/// Byte range of element with ranges of nested elements.
+struct RawNode {
+    start: usize,
+    end: usize,
+    children: Vec<RawNode>,
+}
+
+/// Problem in markup with byte range.
+struct RawDiagnostic {
+    severity: Severity,
+    message: String,
+    range: Option<(usize, usize)>,
+}
+
+struct Recorder {
+    /// Address range of source text.
+    source: (usize, usize),
+    /// Recorded elements, which aren't attached to parent yet.
+    nodes: Vec<RawNode>,
+    diagnostics: Vec<RawDiagnostic>,
+}
+
+/// Counts of recorded elements and problems. Elements recorded after mark are nested in the next element.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Mark {
+    pub nodes: usize,
+    pub diagnostics: usize,
+}
+
+thread_local! {
+    static RECORDER: RefCell<Option<Recorder>> = const { RefCell::new(None) };
+}
+
+/// Calls `f` with recording of element ranges in `source`.
+/// Returns spans of top-level elements and problems in markup.
+pub fn record<T, F: FnOnce() -> T>(source: &str, f: F) -> (T, Vec<SpanNode>, Vec<Diagnostic>) {
+    let start = source.as_ptr() as usize;
+    let previous = RECORDER.with(|recorder| {
+        recorder.borrow_mut().replace(Recorder {
+            source: (start, start + source.len()),
+            nodes: Vec::new(),
+            diagnostics: Vec::new(),
+        })
+    });
+    let res = f();
+    let (nodes, mut diagnostics) = RECORDER.with(|recorder| {
+        let mut recorder = recorder.borrow_mut();
+        let res = recorder.take().map_or_else(Default::default, |recorder| (recorder.nodes, recorder.diagnostics));
+        *recorder = previous;
+        res
+    });
+    let line_starts: Vec<usize> = Some(0).into_iter()
+        .chain(source.match_indices('\n').map(|(idx, _)| idx + 1))
+        .collect();
+    let to_span = |(start, end)| {
+        Span {
+            start: to_position(start, source, &line_starts),
+            end: to_position(end, source, &line_starts),
+        }
+    };
+
+    // The same attributes can be parsed several times, e.g. in table cells.
+    diagnostics.sort_by_key(|diagnostic| diagnostic.range);
+    diagnostics.dedup_by(|a, b| a.range == b.range && a.message == b.message);
+
+    (
+        res,
+        nodes.into_iter().map(|node| to_span_node(node, &to_span)).collect(),
+        diagnostics.into_iter()
+            .map(|diagnostic| {
+                Diagnostic {
+                    severity: diagnostic.severity,
+                    message: diagnostic.message,
+                    span: diagnostic.range.map(&to_span),
+                }
+            })
+            .collect()
+    )
+}
+
+/// Returns counts of recorded elements and problems.
+pub fn mark() -> Mark {
+    RECORDER.with(|recorder| {
+        recorder.borrow().as_ref().map_or_else(Mark::default, |recorder| {
+            Mark {
+                nodes: recorder.nodes.len(),
+                diagnostics: recorder.diagnostics.len(),
+            }
+        })
+    })
+}
+
+/// Forgets elements and problems recorded after mark, e.g. by parser which didn't match.
+pub fn discard(mark: Mark) {
+    RECORDER.with(|recorder| {
+        if let Some(ref mut recorder) = *recorder.borrow_mut() {
+            recorder.nodes.truncate(mark.nodes);
+            recorder.diagnostics.truncate(mark.diagnostics);
+        }
+    });
+}
+
+/// Records problem in markup, which is found in text.
+pub fn report(severity: Severity, message: String, text: &str) {
+    let range = locate(text);
+
+    RECORDER.with(|recorder| {
+        if let Some(ref mut recorder) = *recorder.borrow_mut() {
+            recorder.diagnostics.push(RawDiagnostic { severity, message, range });
+        }
+    });
+}
+
+/// Returns byte range of text in source, if text is borrowed from it.
+pub fn locate(text: &str) -> Option<(usize, usize)> {
+    RECORDER.with(|recorder| {
+        recorder.borrow().as_ref().and_then(|recorder| {
+            let start = text.as_ptr() as usize;
+            let (source_start, source_end) = recorder.source;
+
+            if start >= source_start && start + text.len() <= source_end {
+                Some((start - source_start, start - source_start + text.len()))
+            } else {
+                None
+            }
+        })
+    })
+}
+
+/// Returns byte range from the start of `first` text to the end of `last` one, if both are borrowed from source.
+pub fn locate_range(first: &str, last: &str) -> Option<(usize, usize)> {
+    locate(first).and_then(|(start, _)| locate(last).map(|(_, end)| (start, end.max(start))))
+}
+
+/// Records element, which spans lines from the first non-empty one to the last non-empty one.
+pub fn push_lines(mark: Mark, lines: &[&str]) {
+    let first = lines.iter().find(|line| !line.is_empty());
+    let last = lines.iter().rev().find(|line| !line.is_empty());
+    push(mark, first.and_then(|first| last.and_then(|last| locate_range(first, last))));
+}
+
+/// Records element with byte range and elements recorded after mark as its children.
+pub fn push(mark: Mark, range: Option<(usize, usize)>) {
+    RECORDER.with(|recorder| {
+        if let Some(ref mut recorder) = *recorder.borrow_mut() {
+            // Children of element without range are left to its parent.
+            if let Some((start, end)) = range {
+                let children = recorder.nodes.split_off(mark.nodes.min(recorder.nodes.len()));
+                recorder.nodes.push(RawNode { start, end, children });
+            }
+        }
+    });
+}
+
+fn to_span_node<F: Fn((usize, usize)) -> Span>(node: RawNode, to_span: &F) -> SpanNode {
+    SpanNode {
+        span: to_span((node.start, node.end)),
+        children: node.children.into_iter().map(|node| to_span_node(node, to_span)).collect(),
+    }
+}
+
+fn to_position(offset: usize, source: &str, line_starts: &[usize]) -> Position {
+    let line = match line_starts.binary_search(&offset) {
+        Ok(idx) => idx,
+        Err(idx) => idx - 1,
+    };
+
+    Position {
+        offset,
+        line: line + 1,
+        column: source[line_starts[line]..offset].chars().count() + 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_spans_correctly() {
+        let source = "First\nÜber *bold*";
+        let (_, spans, diagnostics) = record(source, || {
+            let start = mark();
+            push(start, locate(&source[12..16]));
+            push(start, locate(&source[11..17]));
+            push(mark(), locate("Not from source"));
+            report(Severity::Warning, "Warning".to_string(), &source[..5]);
+        });
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic {
+                    severity: Severity::Warning,
+                    message: "Warning".to_string(),
+                    span: Some(Span {
+                        start: Position { offset: 0, line: 1, column: 1 },
+                        end: Position { offset: 5, line: 1, column: 6 },
+                    }),
+                },
+            ]
+        );
+
+        assert_eq!(
+            spans,
+            vec![
+                SpanNode {
+                    span: Span {
+                        start: Position { offset: 11, line: 2, column: 5 },
+                        end: Position { offset: 17, line: 2, column: 11 },
+                    },
+                    children: vec![
+                        SpanNode {
+                            span: Span {
+                                start: Position { offset: 12, line: 2, column: 6 },
+                                end: Position { offset: 16, line: 2, column: 10 },
+                            },
+                            children: Vec::new(),
+                        },
+                    ],
+                },
+            ]
+        );
+    }
+}
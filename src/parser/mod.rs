@@ -1,53 +1,145 @@
 //! Parser module for Textile language.
+//!
+//! # Serialization
+//!
+//! With `serde` feature, elements implement `Serialize` and `Deserialize`. Every variant of enum is object
+//! with `type` in snake case and `content` with fields of variant, which is omitted for variants without
+//! fields. Attributes are object of strings, e.g. `parse("p(intro). Some *text*")` is serialized into JSON as:
+//!
+//! ```json
+//! [
+//!   {
+//!     "type": "paragraph",
+//!     "content": {
+//!       "attributes": { "class": "intro" },
+//!       "elements": [
+//!         { "type": "text", "content": "Some " },
+//!         {
+//!           "type": "bold",
+//!           "content": {
+//!             "attributes": {},
+//!             "elements": [{ "type": "text", "content": "text" }],
+//!             "tag_type": "strong"
+//!           }
+//!         }
+//!       ],
+//!       "starts_with_p": true
+//!     }
+//!   }
+//! ]
+//! ```
+//!
+//! Line break is `{ "type": "break" }`, endnote is plain object with its fields and `NoteBacklinks` is string,
+//! e.g. `"first"`. Names of types and fields follow names in Rust, so they change only with the API.
 
 mod attributes;
 mod block;
+mod diagnostic;
+mod event;
 mod inline;
 mod patterns;
+mod location;
+mod owned;
+mod visit;
 
 use into_string::*;
-use parser::block::parse_blocks;
+pub(crate) use parser::inline::{describe_url, is_abbreviation, parse_inline_elements};
+use parser::block::{extract_link_aliases, parse_blocks, resolve_link_aliases, resolve_notes};
+use parser::owned::into_owned_blocks;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::io;
+use std::mem;
+use std::path::Path;
+
+pub use parser::diagnostic::{Diagnostic, Severity};
+pub use parser::event::{Event, Parser, Tag, from_events};
+pub use parser::location::{Position, Span, SpanNode};
 
 /// Vector of block elements.
-pub type BlockElements = Vec<Block>;
+pub type BlockElements<'a> = Vec<Block<'a>>;
 /// Vector of inline elements.
-pub type InlineElements = Vec<Inline>;
+pub type InlineElements<'a> = Vec<Inline<'a>>;
 /// Vector of Textile attributes, e.g. classes, ID's or CSS styles.
 pub type Attributes = HashMap<String, String>;
+/// Link aliases, e.g. `[rust]https://rust-lang.org`, mapped to their URLs.
+pub type LinkAliases = HashMap<String, String>;
 
 /// Block element, e.g. heading, paragraph or code block.
-#[derive(Debug, PartialEq)]
-pub enum Block {
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "content", rename_all = "snake_case"))]
+pub enum Block<'a> {
     /// Block quotation, e.g. `bq. Some quote`.
     BlockQuotation {
         attributes: Attributes,
-        elements: BlockElements,
+        elements: BlockElements<'a>,
     },
     /// Code block, e.g. `bc. print("Hello World")`.
     CodeBlock {
         attributes: Attributes,
-        code: String,
+        code: Cow<'a, str>,
     },
     /// Comment block.
     Comment(Vec<String>),
+    /// Definition list, e.g. `- Term := Definition` or `; Term` and `: Definition`.
+    DefinitionList {
+        attributes: Attributes,
+        elements: Vec<DefinitionListElement<'a>>,
+    },
+    /// Division, e.g. `div. Some text` or extended `div.. Some text` with several paragraphs.
+    Div {
+        attributes: Attributes,
+        elements: BlockElements<'a>,
+    },
+    /// Figure with image and caption, e.g. `fig. !image.png! Caption`.
+    Figure {
+        attributes: Attributes,
+        caption: InlineElements<'a>,
+        image: Box<Inline<'a>>,
+    },
+    /// Footnote, e.g. `fn1. Some text` or `fn1^. Some text` with link back to reference.
+    Footnote {
+        attributes: Attributes,
+        backlink: bool,
+        elements: InlineElements<'a>,
+        id: String,
+    },
     /// Heading, e.g. `h3. Some text`.
     Heading {
         attributes: Attributes,
-        elements: InlineElements,
+        elements: InlineElements<'a>,
         level: u8,
     },
+    /// Raw HTML block, e.g. `<div>Some text</div>`. Lines are passed into output as is.
+    Html(Vec<String>),
     /// In this block the Textile formatting is disabled.
     NoTextileBlock(Vec<String>),
+    /// Endnote, e.g. `note#label. Some text`. Endnotes are moved into `NoteList` blocks after parsing.
+    Note {
+        attributes: Attributes,
+        elements: InlineElements<'a>,
+        label: String,
+    },
+    /// List of endnotes, e.g. `notelist.` or `notelist:symbol+.`.
+    NoteList {
+        attributes: Attributes,
+        backlinks: NoteBacklinks,
+        elements: Vec<Note<'a>>,
+        symbol: Option<String>,
+        unreferenced: bool,
+    },
     OrderedList {
         attributes: Attributes,
-        elements: Vec<ListElement>,
+        elements: Vec<ListElement<'a>>,
         level: u8,
     },
     /// Paragraph, e.g. `p. Some text` or `Some text`.
     Paragraph {
         attributes: Attributes,
-        elements: InlineElements,
+        elements: InlineElements<'a>,
         starts_with_p: bool,
     },
     /// Pre-formatted text, e.g. `pre. *Some text*`.
@@ -55,17 +147,47 @@ pub enum Block {
         attributes: Attributes,
         lines: Vec<String>,
     },
+    /// Table, e.g. `|_. Name|_. Age|`.
+    Table {
+        attributes: Attributes,
+        elements: Vec<TableElement<'a>>,
+    },
     /// Unordered list, e.g. `* List item`.
     UnorderedList {
         attributes: Attributes,
-        elements: Vec<ListElement>,
+        elements: Vec<ListElement<'a>>,
         level: u8,
     }
 }
 
+impl<'a> Block<'a> {
+    /// Returns attributes of block element, if it has them.
+    pub fn attributes(&self) -> Option<&Attributes> {
+        match *self {
+            Block::BlockQuotation { ref attributes, .. } |
+            Block::CodeBlock { ref attributes, .. } |
+            Block::DefinitionList { ref attributes, .. } |
+            Block::Div { ref attributes, .. } |
+            Block::Figure { ref attributes, .. } |
+            Block::Footnote { ref attributes, .. } |
+            Block::Heading { ref attributes, .. } |
+            Block::Note { ref attributes, .. } |
+            Block::NoteList { ref attributes, .. } |
+            Block::OrderedList { ref attributes, .. } |
+            Block::Paragraph { ref attributes, .. } |
+            Block::Pre { ref attributes, .. } |
+            Block::Table { ref attributes, .. } |
+            Block::UnorderedList { ref attributes, .. } => Some(attributes),
+            Block::Comment(_) | Block::Html(_) | Block::NoTextileBlock(_) => None,
+        }
+    }
+}
+
 /// Inline element, e.g. bold text, link or image.
-#[derive(Debug, PartialEq)]
-pub enum Inline {
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "content", rename_all = "snake_case"))]
+pub enum Inline<'a> {
     /// Abbreviation, e.g. `ABBR(Abbreviation)`.
     Abbreviation {
         abbr: String,
@@ -74,7 +196,7 @@ pub enum Inline {
     /// Bold text, e.g. `*Text*` or `**Text**`.
     Bold {
         attributes: Attributes,
-        elements: InlineElements,
+        elements: InlineElements<'a>,
         tag_type: String,
     },
     /// Line break. Converts to `<br>` tag in HTML.
@@ -82,11 +204,18 @@ pub enum Inline {
     /// Citation, e.g. `??Some citation??`.
     Citation {
         attributes: Attributes,
-        elements: InlineElements,
+        elements: InlineElements<'a>,
     },
     /// Code, e.g. `@puts "Hello world!"@`.
-    Code(String),
-    /// Image, e.g. `!http://example.com/image.jpg(Image)!`.
+    Code(Cow<'a, str>),
+    /// Reference to footnote, e.g. `Text[1]` or `Text[1]!` without link to footnote.
+    FootnoteRef {
+        id: String,
+        link: bool,
+    },
+    /// Inline HTML tag, e.g. `<kbd>` or `</kbd>`. Passed into output as is.
+    Html(Cow<'a, str>),
+    /// Image, e.g. `!http://example.com/image.jpg(Image)!` or `!image.jpg 100x50!` with width and height.
     Image {
         attributes: Attributes,
         href: String,
@@ -94,53 +223,172 @@ pub enum Inline {
     /// Italic text, e.g. `_Text_` or `__Text__`.
     Italic {
         attributes: Attributes,
-        elements: InlineElements,
+        elements: InlineElements<'a>,
         tag_type: String,
     },
     /// Link, e.g. `"Link":http://example.com`.
     Link {
         attributes: Attributes,
-        elements: InlineElements,
+        elements: InlineElements<'a>,
+    },
+    /// Text with disabled Textile formatting, e.g. `==*Text*==`. Passed into output as is.
+    NoTextile(Cow<'a, str>),
+    /// Reference to endnote, e.g. `[#label]` or `[#label!]` without link to note.
+    /// Number of note and index of reference are assigned after parsing of the whole document.
+    NoteRef {
+        label: String,
+        link: bool,
+        number: usize,
+        reference: usize,
     },
     /// Span element, e.g. `%Span text%`.
     Span {
         attributes: Attributes,
-        elements: InlineElements,
+        elements: InlineElements<'a>,
     },
     /// Strikethrough text, e.g. `-Text-`.
     Strikethrough {
         attributes: Attributes,
-        elements: InlineElements,
+        elements: InlineElements<'a>,
     },
     /// Subscript text, e.g. `~Text~`.
     Subscript {
         attributes: Attributes,
-        elements: InlineElements,
+        elements: InlineElements<'a>,
     },
     /// Superscript text, e.g. `^Text^`.
     Superscript {
         attributes: Attributes,
-        elements: InlineElements,
+        elements: InlineElements<'a>,
     },
     /// String with text.
-    Text(String),
+    Text(Cow<'a, str>),
     /// Underlined text, e.g. `+Text+`.
     Underlined {
         attributes: Attributes,
-        elements: InlineElements,
+        elements: InlineElements<'a>,
     },
 }
 
-#[derive(Debug, PartialEq)]
-pub enum ListElement {
+impl<'a> Inline<'a> {
+    /// Returns attributes of inline element, if it has them.
+    pub fn attributes(&self) -> Option<&Attributes> {
+        match *self {
+            Inline::Bold { ref attributes, .. } |
+            Inline::Citation { ref attributes, .. } |
+            Inline::Image { ref attributes, .. } |
+            Inline::Italic { ref attributes, .. } |
+            Inline::Link { ref attributes, .. } |
+            Inline::Span { ref attributes, .. } |
+            Inline::Strikethrough { ref attributes, .. } |
+            Inline::Subscript { ref attributes, .. } |
+            Inline::Superscript { ref attributes, .. } |
+            Inline::Underlined { ref attributes, .. } => Some(attributes),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "content", rename_all = "snake_case"))]
+pub enum ListElement<'a> {
     ListItem {
         attributes: Attributes,
-        elements: InlineElements,
+        elements: InlineElements<'a>,
+    },
+    List(Block<'a>),
+}
+
+/// Definition list element: term or its definition.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "content", rename_all = "snake_case"))]
+pub enum DefinitionListElement<'a> {
+    /// Definition of term. Converts to `<dd>` tag in HTML.
+    Definition {
+        attributes: Attributes,
+        elements: InlineElements<'a>,
+    },
+    /// Defined term. Converts to `<dt>` tag in HTML.
+    Term {
+        attributes: Attributes,
+        elements: InlineElements<'a>,
     },
-    List(Block),
 }
 
-/// Splits text into tokens. Accepts `&str`, `String` or `Path` data type. Returns vector of block elements.
+/// Endnote collected into list of notes.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Note<'a> {
+    pub attributes: Attributes,
+    pub elements: InlineElements<'a>,
+    pub label: String,
+    /// Number of note in order of the first reference, or `None` if note isn't referenced.
+    pub number: Option<usize>,
+    /// Count of references to note.
+    pub references: usize,
+}
+
+/// Links from list of endnotes back to references.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum NoteBacklinks {
+    /// Links to all references, e.g. `notelist.`.
+    All,
+    /// Link to the first reference only, e.g. `notelist^.`.
+    First,
+    /// No links, e.g. `notelist!.`.
+    Disabled,
+}
+
+/// Table element, e.g. caption, row or section.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "content", rename_all = "snake_case"))]
+pub enum TableElement<'a> {
+    /// Table caption, e.g. `|=. Caption`.
+    Caption {
+        attributes: Attributes,
+        elements: InlineElements<'a>,
+    },
+    /// Group of columns, e.g. `|:\3. 100|50|`. Every item of `cols` is attributes of single column.
+    ColGroup {
+        attributes: Attributes,
+        cols: Vec<Attributes>,
+    },
+    /// Table row, e.g. `(class). |Cell|Cell|`.
+    Row {
+        attributes: Attributes,
+        cells: Vec<TableCell<'a>>,
+    },
+    /// Section of rows: `|^.` for `thead`, `|-.` for `tbody` or `|~.` for `tfoot`.
+    Section {
+        attributes: Attributes,
+        rows: Vec<TableElement<'a>>,
+        tag_type: String,
+    },
+}
+
+/// Table cell, e.g. `|_. Header|` or `|Data|`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "content", rename_all = "snake_case"))]
+pub enum TableCell<'a> {
+    /// Data cell. Converts to `<td>` tag in HTML.
+    Data {
+        attributes: Attributes,
+        elements: InlineElements<'a>,
+    },
+    /// Header cell, e.g. `|_. Header|`. Converts to `<th>` tag in HTML.
+    Header {
+        attributes: Attributes,
+        elements: InlineElements<'a>,
+    },
+}
+
+/// Splits text into tokens. Accepts `&str` or `String` data type. Returns vector of block elements.
 ///
 /// # Example
 ///
@@ -156,15 +404,181 @@ pub enum ListElement {
 ///                 attributes: Attributes::new(),
 ///                 tag_type: "em".to_string(),
 ///                 elements: vec![
-///                     Inline::Text("String with text".to_string())
+///                     Inline::Text("String with text".into())
 ///                 ]
 ///             },
-///             Inline::Text(".".to_string())
+///             Inline::Text(".".into())
 ///         ],
 ///         level: 1
 ///     }
 /// ]);
 /// ```
-pub fn parse<S: IntoString>(text: S) -> BlockElements {
-    parse_blocks(&text.into_string().lines().collect::<Vec<&str>>())
+pub fn parse<S: IntoString>(text: S) -> BlockElements<'static> {
+    parse_with_aliases(text).0
+}
+
+/// Parses contents of file. Returns error, if file can't be read or isn't valid UTF-8.
+///
+/// # Example
+///
+/// ```rust
+/// use textile::parser::*;
+///
+/// assert!(parse_file("missing.textile").is_err());
+/// ```
+pub fn parse_file<P: AsRef<Path>>(path: P) -> io::Result<BlockElements<'static>> {
+    read_file(path).map(parse)
+}
+
+/// Parses text read from reader. The whole text is read before parsing, because link aliases
+/// and notes may be defined anywhere in document. Returns error, if reading fails or text
+/// isn't valid UTF-8.
+///
+/// # Example
+///
+/// ```rust
+/// use textile::parser::*;
+///
+/// let blocks = parse_reader("h1. Heading".as_bytes()).unwrap();
+/// assert_eq!(blocks.len(), 1);
+/// assert!(parse_reader(&[0xff, 0xfe][..]).is_err());
+/// ```
+pub fn parse_reader<R: io::Read>(reader: R) -> io::Result<BlockElements<'static>> {
+    read_string(reader).map(parse)
+}
+
+/// Same as `parse`, but also returns link aliases defined in document, e.g. `[rust]https://rust-lang.org`.
+/// Links to alias, e.g. `"Rust":rust`, are resolved in returned elements.
+///
+/// # Example
+///
+/// ```rust
+/// use textile::parser::*;
+///
+/// let (_, aliases) = parse_with_aliases("\"Rust\":rust\n\n[rust]https://rust-lang.org");
+/// assert_eq!(aliases.get("rust"), Some(&"https://rust-lang.org".to_string()));
+/// ```
+pub fn parse_with_aliases<S: IntoString>(text: S) -> (BlockElements<'static>, LinkAliases) {
+    let text = text.into_string();
+    let (mut blocks, aliases) = parse_lines(&text);
+    resolve_notes(&mut blocks);
+    (into_owned_blocks(blocks), aliases)
+}
+
+/// Same as `parse`, but returned elements borrow text from document instead of copying it,
+/// where it's possible. Use `into_owned` method of element to detach it from document.
+///
+/// # Example
+///
+/// ```rust
+/// use std::borrow::Cow;
+/// use textile::parser::*;
+///
+/// let text = "Some text";
+/// match parse_borrowed(text)[0] {
+///     Block::Paragraph { ref elements, .. } => assert_eq!(elements[0], Inline::Text(Cow::Borrowed("Some text"))),
+///     _ => unreachable!(),
+/// }
+/// ```
+pub fn parse_borrowed(text: &str) -> BlockElements<'_> {
+    let (mut blocks, _) = parse_lines(text);
+    resolve_notes(&mut blocks);
+    blocks
+}
+
+/// Same as `parse`, but also returns source positions of elements. Every returned block has
+/// span node at the same index, except endnotes, which are moved into lists of notes.
+/// Children of span node are spans of nested elements in the order of the parse tree:
+///
+/// * blocks of block quotations and divisions;
+/// * items of lists, where nested list is a single node with its items as children;
+/// * terms and definitions of definition lists;
+/// * caption, column groups, sections and rows of tables, where rows have cells as children;
+/// * endnotes of lists of notes;
+/// * inline elements of other blocks, items, cells and endnotes, or nested in inline ones.
+///
+/// # Example
+///
+/// ```rust
+/// use textile::parser::*;
+///
+/// let (blocks, spans) = parse_with_spans("h1. Heading\n\nSome *bold* text");
+/// assert_eq!(blocks.len(), spans.len());
+/// assert_eq!((spans[1].span.start.line, spans[1].span.start.column), (3, 1));
+///
+/// let bold = &spans[1].children[1].span;
+/// assert_eq!((bold.start.offset, bold.end.offset), (18, 24));
+/// ```
+pub fn parse_with_spans<S: IntoString>(text: S) -> (BlockElements<'static>, Vec<SpanNode>) {
+    let text = text.into_string();
+    let ((mut blocks, _), mut spans, _) = location::record(&text, || parse_lines(&text));
+
+    // Spans of endnotes by label, which are moved into lists of notes as endnotes.
+    let mut notes: Vec<(String, SpanNode)> = Vec::new();
+
+    if spans.len() == blocks.len() {
+        for (block, node) in blocks.iter().zip(mem::take(&mut spans)) {
+            match *block {
+                Block::Note { ref label, .. } => {
+                    if !notes.iter().any(|note| note.0 == *label) {
+                        notes.push((label.clone(), node));
+                    }
+                }
+                _ => spans.push(node),
+            }
+        }
+    }
+    resolve_notes(&mut blocks);
+    add_note_spans(&blocks, &mut spans, &notes);
+    (into_owned_blocks(blocks), spans)
+}
+
+/// Sets spans of endnotes as children of span nodes of lists of notes.
+fn add_note_spans(blocks: &[Block], spans: &mut [SpanNode], notes: &[(String, SpanNode)]) {
+    if blocks.len() != spans.len() {
+        return;
+    }
+
+    for (block, node) in blocks.iter().zip(spans) {
+        match *block {
+            Block::NoteList { ref elements, .. } => {
+                node.children = elements.iter()
+                    .filter_map(|element| notes.iter().find(|note| note.0 == element.label))
+                    .map(|note| note.1.clone())
+                    .collect();
+            }
+            Block::BlockQuotation { ref elements, .. } | Block::Div { ref elements, .. } => {
+                add_note_spans(elements, &mut node.children, notes);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Same as `parse`, but also returns problems in markup, e.g. unclosed bold text or malformed attributes.
+/// Markup with problems is parsed as usual, e.g. unclosed bold text is parsed as text.
+///
+/// # Example
+///
+/// ```rust
+/// use textile::parser::*;
+///
+/// let (_, diagnostics) = parse_checked("p{color}. Some *unclosed text");
+/// assert_eq!(diagnostics.len(), 2);
+/// assert_eq!(diagnostics[1].severity, Severity::Warning);
+/// assert_eq!(diagnostics[1].span.map(|span| span.start.column), Some(16));
+/// ```
+pub fn parse_checked<S: IntoString>(text: S) -> (BlockElements<'static>, Vec<Diagnostic>) {
+    let text = text.into_string();
+    let ((mut blocks, _), _, diagnostics) = location::record(&text, || parse_lines(&text));
+    resolve_notes(&mut blocks);
+    (into_owned_blocks(blocks), diagnostics)
+}
+
+/// Parses lines of text into blocks with resolved link aliases.
+fn parse_lines(text: &str) -> (BlockElements<'_>, LinkAliases) {
+    let (lines, aliases) = extract_link_aliases(&text.lines().collect::<Vec<&str>>());
+    let mut blocks = parse_blocks(&lines);
+    resolve_link_aliases(&mut blocks, &aliases);
+    (blocks, aliases)
 }
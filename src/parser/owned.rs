@@ -0,0 +1,247 @@
+//! Conversion of elements, which borrow text from document, into owned ones.
+
+use parser::{Block, BlockElements, DefinitionListElement, Inline, InlineElements, ListElement, Note, TableCell,
+             TableElement};
+use std::borrow::Cow;
+
+impl<'a> Block<'a> {
+    /// Converts block element into one, which owns all its text and doesn't borrow document.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use textile::parser::*;
+    ///
+    /// let block = {
+    ///     let text = String::from("bc. Some code");
+    ///     parse_borrowed(&text).remove(0).into_owned()
+    /// };
+    /// assert_eq!(block, parse("bc. Some code")[0]);
+    /// ```
+    pub fn into_owned(self) -> Block<'static> {
+        match self {
+            Block::BlockQuotation { attributes, elements } => {
+                Block::BlockQuotation { attributes, elements: into_owned_blocks(elements) }
+            }
+            Block::CodeBlock { attributes, code } => Block::CodeBlock { attributes, code: owned(code) },
+            Block::Comment(lines) => Block::Comment(lines),
+            Block::DefinitionList { attributes, elements } => Block::DefinitionList {
+                attributes,
+                elements: elements.into_iter().map(DefinitionListElement::into_owned).collect(),
+            },
+            Block::Div { attributes, elements } => Block::Div { attributes, elements: into_owned_blocks(elements) },
+            Block::Figure { attributes, caption, image } => Block::Figure {
+                attributes,
+                caption: into_owned_inlines(caption),
+                image: Box::new(image.into_owned()),
+            },
+            Block::Footnote { attributes, backlink, elements, id } => {
+                Block::Footnote { attributes, backlink, elements: into_owned_inlines(elements), id }
+            }
+            Block::Heading { attributes, elements, level } => {
+                Block::Heading { attributes, elements: into_owned_inlines(elements), level }
+            }
+            Block::Html(lines) => Block::Html(lines),
+            Block::NoTextileBlock(lines) => Block::NoTextileBlock(lines),
+            Block::Note { attributes, elements, label } => {
+                Block::Note { attributes, elements: into_owned_inlines(elements), label }
+            }
+            Block::NoteList { attributes, backlinks, elements, symbol, unreferenced } => Block::NoteList {
+                attributes,
+                backlinks,
+                elements: elements.into_iter().map(Note::into_owned).collect(),
+                symbol,
+                unreferenced,
+            },
+            Block::OrderedList { attributes, elements, level } => Block::OrderedList {
+                attributes,
+                elements: elements.into_iter().map(ListElement::into_owned).collect(),
+                level,
+            },
+            Block::Paragraph { attributes, elements, starts_with_p } => {
+                Block::Paragraph { attributes, elements: into_owned_inlines(elements), starts_with_p }
+            }
+            Block::Pre { attributes, lines } => Block::Pre { attributes, lines },
+            Block::Table { attributes, elements } => Block::Table {
+                attributes,
+                elements: elements.into_iter().map(TableElement::into_owned).collect(),
+            },
+            Block::UnorderedList { attributes, elements, level } => Block::UnorderedList {
+                attributes,
+                elements: elements.into_iter().map(ListElement::into_owned).collect(),
+                level,
+            },
+        }
+    }
+}
+
+impl<'a> Inline<'a> {
+    /// Converts inline element into one, which owns all its text and doesn't borrow document.
+    pub fn into_owned(self) -> Inline<'static> {
+        match self {
+            Inline::Abbreviation { abbr, transcript } => Inline::Abbreviation { abbr, transcript },
+            Inline::Bold { attributes, elements, tag_type } => {
+                Inline::Bold { attributes, elements: into_owned_inlines(elements), tag_type }
+            }
+            Inline::Break => Inline::Break,
+            Inline::Citation { attributes, elements } => {
+                Inline::Citation { attributes, elements: into_owned_inlines(elements) }
+            }
+            Inline::Code(code) => Inline::Code(owned(code)),
+            Inline::FootnoteRef { id, link } => Inline::FootnoteRef { id, link },
+            Inline::Html(html) => Inline::Html(owned(html)),
+            Inline::Image { attributes, href } => Inline::Image { attributes, href },
+            Inline::Italic { attributes, elements, tag_type } => {
+                Inline::Italic { attributes, elements: into_owned_inlines(elements), tag_type }
+            }
+            Inline::Link { attributes, elements } => Inline::Link { attributes, elements: into_owned_inlines(elements) },
+            Inline::NoTextile(text) => Inline::NoTextile(owned(text)),
+            Inline::NoteRef { label, link, number, reference } => Inline::NoteRef { label, link, number, reference },
+            Inline::Span { attributes, elements } => Inline::Span { attributes, elements: into_owned_inlines(elements) },
+            Inline::Strikethrough { attributes, elements } => {
+                Inline::Strikethrough { attributes, elements: into_owned_inlines(elements) }
+            }
+            Inline::Subscript { attributes, elements } => {
+                Inline::Subscript { attributes, elements: into_owned_inlines(elements) }
+            }
+            Inline::Superscript { attributes, elements } => {
+                Inline::Superscript { attributes, elements: into_owned_inlines(elements) }
+            }
+            Inline::Text(text) => Inline::Text(owned(text)),
+            Inline::Underlined { attributes, elements } => {
+                Inline::Underlined { attributes, elements: into_owned_inlines(elements) }
+            }
+        }
+    }
+}
+
+impl<'a> ListElement<'a> {
+    /// Converts list element into one, which owns all its text.
+    pub fn into_owned(self) -> ListElement<'static> {
+        match self {
+            ListElement::ListItem { attributes, elements } => {
+                ListElement::ListItem { attributes, elements: into_owned_inlines(elements) }
+            }
+            ListElement::List(list) => ListElement::List(list.into_owned()),
+        }
+    }
+}
+
+impl<'a> DefinitionListElement<'a> {
+    /// Converts definition list element into one, which owns all its text.
+    pub fn into_owned(self) -> DefinitionListElement<'static> {
+        match self {
+            DefinitionListElement::Definition { attributes, elements } => {
+                DefinitionListElement::Definition { attributes, elements: into_owned_inlines(elements) }
+            }
+            DefinitionListElement::Term { attributes, elements } => {
+                DefinitionListElement::Term { attributes, elements: into_owned_inlines(elements) }
+            }
+        }
+    }
+}
+
+impl<'a> Note<'a> {
+    /// Converts endnote into one, which owns all its text.
+    pub fn into_owned(self) -> Note<'static> {
+        Note {
+            attributes: self.attributes,
+            elements: into_owned_inlines(self.elements),
+            label: self.label,
+            number: self.number,
+            references: self.references,
+        }
+    }
+}
+
+impl<'a> TableElement<'a> {
+    /// Converts table element into one, which owns all its text.
+    pub fn into_owned(self) -> TableElement<'static> {
+        match self {
+            TableElement::Caption { attributes, elements } => {
+                TableElement::Caption { attributes, elements: into_owned_inlines(elements) }
+            }
+            TableElement::ColGroup { attributes, cols } => TableElement::ColGroup { attributes, cols },
+            TableElement::Row { attributes, cells } => TableElement::Row {
+                attributes,
+                cells: cells.into_iter().map(TableCell::into_owned).collect(),
+            },
+            TableElement::Section { attributes, rows, tag_type } => TableElement::Section {
+                attributes,
+                rows: rows.into_iter().map(TableElement::into_owned).collect(),
+                tag_type,
+            },
+        }
+    }
+}
+
+impl<'a> TableCell<'a> {
+    /// Converts table cell into one, which owns all its text.
+    pub fn into_owned(self) -> TableCell<'static> {
+        match self {
+            TableCell::Data { attributes, elements } => {
+                TableCell::Data { attributes, elements: into_owned_inlines(elements) }
+            }
+            TableCell::Header { attributes, elements } => {
+                TableCell::Header { attributes, elements: into_owned_inlines(elements) }
+            }
+        }
+    }
+}
+
+/// Converts block elements into owned ones.
+pub fn into_owned_blocks(blocks: BlockElements) -> BlockElements<'static> {
+    blocks.into_iter().map(Block::into_owned).collect()
+}
+
+fn into_owned_inlines(elements: InlineElements) -> InlineElements<'static> {
+    elements.into_iter().map(Inline::into_owned).collect()
+}
+
+fn owned(text: Cow<str>) -> Cow<'static, str> {
+    Cow::Owned(text.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{Attributes, Block, Inline, parse_borrowed};
+    use std::borrow::Cow;
+
+    #[test]
+    fn converts_borrowed_elements_into_owned_correctly() {
+        let text = String::from("*Some* text\n\nbc. code");
+        let blocks = parse_borrowed(&text);
+
+        match blocks[0] {
+            Block::Paragraph { ref elements, .. } => assert!(matches!(elements[1], Inline::Text(Cow::Borrowed(" text")))),
+            _ => panic!("paragraph isn't parsed"),
+        }
+        let blocks: Vec<Block<'static>> = blocks.into_iter().map(Block::into_owned).collect();
+        drop(text);
+
+        assert_eq!(
+            blocks,
+            vec![
+                Block::Paragraph {
+                    attributes: Attributes::new(),
+                    elements: vec![
+                        Inline::Bold {
+                            attributes: Attributes::new(),
+                            elements: vec![
+                                Inline::Text("Some".into()),
+                            ],
+                            tag_type: "strong".to_string(),
+                        },
+                        Inline::Text(" text".into()),
+                    ],
+                    starts_with_p: false,
+                },
+                Block::CodeBlock {
+                    attributes: Attributes::new(),
+                    code: "code".into(),
+                },
+            ]
+        );
+        assert!(matches!(blocks[1], Block::CodeBlock { code: Cow::Owned(_), .. }));
+    }
+}
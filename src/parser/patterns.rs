@@ -4,28 +4,39 @@ lazy_static! {
     pub static ref BLOCK_QUOTATION_PATTERN: Regex = Regex::new("^bq(?P<attributes>.*?)(?P<mode>\\.{1,2})(?::(?P<cite>.+?))? ").unwrap();
     pub static ref CODE_BLOCK_PATTERN: Regex = Regex::new("^bc(?P<attributes>.*?)(?P<mode>\\.{1,2}) ").unwrap();
     pub static ref COMMENT_PATTERN: Regex = Regex::new("^#{3}(?P<mode>\\.{1,2}) ").unwrap();
+    pub static ref DEFINITION_LIST_PATTERN: Regex = Regex::new("^(?P<type>[-;:])(?P<attributes>(?:[\\[\\{\\(].+?[\\]\\}\\)])*) +").unwrap();
+    pub static ref DIV_PATTERN: Regex = Regex::new(r"^div(?P<attributes>.*?)(?P<mode>\.{1,2}) ").unwrap();
+    pub static ref FIGURE_PATTERN: Regex = Regex::new(r"^fig(?P<attributes>(?:<>|[<>=\(\)]|\([^\)]*\)|\{[^\}]*\}|\[[^\]]*\])*)\. ").unwrap();
+    pub static ref FOOTNOTE_PATTERN: Regex = Regex::new("^fn(?P<id>\\d+)(?P<backlink>\\^)?(?P<attributes>.*?)\\. ").unwrap();
     pub static ref HEADING_PATTERN: Regex = Regex::new("^h(?P<level>[1-6])(?P<attributes>.*)\\. ").unwrap();
+    pub static ref HTML_BLOCK_PATTERN: Regex = Regex::new(r"(?i)^<(?P<tag>address|article|aside|blockquote|canvas|details|dialog|div|dl|fieldset|figcaption|figure|footer|form|h[1-6]|header|hr|main|nav|noscript|ol|p|pre|script|section|style|table|ul|video)(?:\s[^>]*?)?(?P<self_closing>/)?>").unwrap();
+    pub static ref LINK_ALIAS_PATTERN: Regex = Regex::new(r"^\[(?P<alias>[^\[\]\s\d][^\[\]\s]*)\](?P<href>\S+) *$").unwrap();
     pub static ref NO_TEXTILE_BLOCK_PATTERN: Regex = Regex::new("^notextile(?P<mode>\\.{1,2}) ").unwrap();
-    pub static ref ORDERED_LIST_PATTERN: Regex = Regex::new("(?P<level>#+)(?P<attributes>(?:[\\[\\{\\(].+?[\\]\\}\\)])*)(?P<start>\\d+)?(?:(?:\\. *)|(?: +)|(?:_ +))").unwrap();
+    pub static ref NOTE_PATTERN: Regex = Regex::new(r"^note#(?P<label>[\w-]+)(?P<attributes>.*?)\. ").unwrap();
+    pub static ref NOTE_LIST_PATTERN: Regex = Regex::new(r"^notelist(?P<attributes>[^:]*?)(?::(?P<symbol>[^\s\^!\+\.]+))?(?P<flags>[\^!\+]*)\. *$").unwrap();
+    pub static ref ORDERED_LIST_PATTERN: Regex = Regex::new("^(?P<level>#+)(?P<attributes>(?:[\\[\\{\\(].+?[\\]\\}\\)])*)(?P<start>\\d+)?(?:(?:\\. *)|(?: +)|(?:_ +))").unwrap();
     pub static ref PARAGRAPH_PATTERN: Regex = Regex::new("(?:^p(?P<attributes>.*)\\. )?").unwrap();
     pub static ref PRE_PATTERN: Regex = Regex::new("^pre(?P<attributes>.*?)(?P<mode>\\.{1,2}) ").unwrap();
-    pub static ref UNORDERED_LIST_PATTERN: Regex = Regex::new("(?P<level>\\*+)(?P<attributes>(?:[\\[\\{\\(].+?[\\]\\}\\)])*)(?:(?:\\. *)|(?: +)|(?:_ +))").unwrap();
+    pub static ref TABLE_PATTERN: Regex = Regex::new("^table(?P<attributes>.*?)\\. *$").unwrap();
+    pub static ref TABLE_CAPTION_PATTERN: Regex = Regex::new(r"^\|=(?P<attributes>[^\|]*?)\. (?P<string>[^\|]*)\|? *$").unwrap();
+    pub static ref TABLE_COLGROUP_PATTERN: Regex = Regex::new(r"^\|:(?P<spec>.*?)\|? *$").unwrap();
+    pub static ref TABLE_COL_PATTERN: Regex = Regex::new(r"^(?P<attributes>(?:\\\d+|\([^\)]*\)|\{[^\}]*\}|\[[^\]]*\])*)\.? *(?P<width>\d+)? *$").unwrap();
+    pub static ref TABLE_ROW_PATTERN: Regex = Regex::new(r"^(?:(?P<attributes>(?:<>|[<>=\^~-]|\([^\)]*\)|\{[^\}]*\}|\[[^\]]*\])+)\. )?\|(?P<cells>.*)\| *$").unwrap();
+    pub static ref TABLE_CELL_PATTERN: Regex = Regex::new(r"^(?P<header>_)?(?P<attributes>(?:<>|[<>=\^~-]|\\\d+|/\d+|\([^\)]*\)|\{[^\}]*\}|\[[^\]]*\])*)\.(?: |$)").unwrap();
+    pub static ref TABLE_SECTION_PATTERN: Regex = Regex::new(r"^\|(?P<section>[\^~-])(?P<attributes>[^\|]*?)\. *$").unwrap();
+    pub static ref UNORDERED_LIST_PATTERN: Regex = Regex::new("^(?P<level>\\*+)(?P<attributes>(?:[\\[\\{\\(].+?[\\]\\}\\)])*)(?:(?:\\. *)|(?: +)|(?:_ +))").unwrap();
 
     pub static ref ABBREVIATION_PATTERN: Regex = Regex::new(r"^(?P<abbreviation>\p{Lu}{3,})(?:\((?P<transcript>.*?)\))?").unwrap();
-    pub static ref BOLD_TEXT_PATTERN: Regex = Regex::new(r"^(?P<count1>\*+)(?P<string>.+?)(?P<count2>\*+)").unwrap();
-    pub static ref CITATION_PATTERN: Regex = Regex::new(r"^\?\?(?P<string>.+?)\?\?").unwrap();
     pub static ref CODE_PATTERN: Regex = Regex::new("^@(?P<code>.*?)@").unwrap();
+    pub static ref FOOTNOTE_REF_PATTERN: Regex = Regex::new(r"^\[(?P<id>\d+)\](?P<nolink>!)?").unwrap();
+    pub static ref HTML_INLINE_PATTERN: Regex = Regex::new(r"^<(?:/?[A-Za-z][A-Za-z0-9-]*(?:\s[^<>]*)?/?|!--.*?--)>").unwrap();
     pub static ref IMAGE_PATTERN: Regex = Regex::new("^!(?P<align>[<|>|=]?)(?P<string>.+?)!(?::(?P<href>[^ \\(\\)]+))?").unwrap();
     pub static ref IMAGE_ALT_PATTERN: Regex = Regex::new("(?:\\(([^\\(\\)]+)\\))?$").unwrap();
-    pub static ref ITALIC_TEXT_PATTERN: Regex = Regex::new("^(?P<count1>_+)(?P<string>.+?)(?P<count2>_+)").unwrap();
+    pub static ref IMAGE_SIZE_PATTERN: Regex = Regex::new(r" +(?P<width>\d+)x(?P<height>\d+)$").unwrap();
     pub static ref LINK_PATTERN: Regex = Regex::new("^\"(?P<string>.+?)\":(?P<href>[^ \\(\\)]+)").unwrap();
     pub static ref LINK_TITLE_PATTERN: Regex = Regex::new("(?:\\(([^\\(\\)]+)\\))?$").unwrap();
     pub static ref NO_TEXTILE_INLINE_PATTERN: Regex = Regex::new("^={2}(?P<string>.*?)={2}").unwrap();
-    pub static ref SPAN_PATTERN: Regex = Regex::new("^(?P<count1>%+)(?P<string>.+?)(?P<count2>%+)").unwrap();
-    pub static ref STRIKETHROUGH_TEXT_PATTERN: Regex = Regex::new("^(?P<count1>-+)(?P<string>.+?)(?P<count2>-+)").unwrap();
-    pub static ref SUBSCRIPT_TEXT_PATTERN: Regex = Regex::new("^(?P<count1>~+)(?P<string>.+?)(?P<count2>~+)").unwrap();
-    pub static ref SUPERSCRIPT_TEXT_PATTERN: Regex = Regex::new(r"^(?P<count1>\^+)(?P<string>.+?)(?P<count2>\^+)").unwrap();
-    pub static ref UNDERLINED_TEXT_PATTERN: Regex = Regex::new(r"^(?P<count1>\++)(?P<string>.+?)(?P<count2>\++)").unwrap();
+    pub static ref NOTE_REF_PATTERN: Regex = Regex::new(r"^\[#(?P<label>[\w-]+)(?P<nolink>!)?\]").unwrap();
 
     pub static ref ATTRS_STR_PATTERN: Regex = Regex::new("(?:^([\\[\\{\\(].+?[\\]\\}\\)])*)").unwrap();
     pub static ref PADDING_PATTERN: Regex = Regex::new("\\(+|\\)+").unwrap();
@@ -34,5 +45,8 @@ lazy_static! {
     pub static ref CLASS_ID_PATTERN: Regex = Regex::new("\\((?P<class>[\\w-_\\. ]+)?(?:#(?P<id>[\\w-_]+))?\\)").unwrap();
     pub static ref CSS_PROPS_PATTERN: Regex = Regex::new("\\{([^\\{\\}]+)\\}").unwrap();
     pub static ref CSS_PROPS_SPLIT_PATTERN: Regex = Regex::new("; *").unwrap();
+    pub static ref COLSPAN_PATTERN: Regex = Regex::new(r"\\(\d+)").unwrap();
+    pub static ref ROWSPAN_PATTERN: Regex = Regex::new(r"/(\d+)").unwrap();
+    pub static ref VERTICAL_ALIGN_PATTERN: Regex = Regex::new(r"[\^~-]").unwrap();
     pub static ref CSS_PROP_STR_PATTERN: Regex = Regex::new("(?P<key>[a-z-_]+): *(?P<value>.+)").unwrap();
 }
@@ -0,0 +1,104 @@
+//! Traversal of parse tree for passes over the whole document.
+
+use parser::{Block, DefinitionListElement, Inline, ListElement, TableCell, TableElement};
+
+/// Calls `f` for every block element, including nested ones, in document order.
+pub fn visit_blocks_mut<'a, F: FnMut(&mut Block<'a>)>(blocks: &mut [Block<'a>], f: &mut F) {
+    for block in blocks {
+        f(block);
+
+        match *block {
+            Block::BlockQuotation { ref mut elements, .. } |
+            Block::Div { ref mut elements, .. } => visit_blocks_mut(elements, f),
+            Block::OrderedList { ref mut elements, .. } |
+            Block::UnorderedList { ref mut elements, .. } => {
+                for element in elements {
+                    if let ListElement::List(ref mut list) = *element {
+                        visit_blocks_mut(::std::slice::from_mut(list), f);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Calls `f` for every inline element, including nested ones, in document order.
+pub fn visit_inline_elements_mut<'a, F: FnMut(&mut Inline<'a>)>(blocks: &mut [Block<'a>], f: &mut F) {
+    for block in blocks {
+        match *block {
+            Block::BlockQuotation { ref mut elements, .. } |
+            Block::Div { ref mut elements, .. } => visit_inline_elements_mut(elements, f),
+            Block::DefinitionList { ref mut elements, .. } => {
+                for element in elements {
+                    match *element {
+                        DefinitionListElement::Definition { ref mut elements, .. } |
+                        DefinitionListElement::Term { ref mut elements, .. } => visit_inlines_mut(elements, f),
+                    }
+                }
+            }
+            Block::Figure { ref mut caption, ref mut image, .. } => {
+                visit_inlines_mut(::std::slice::from_mut(&mut **image), f);
+                visit_inlines_mut(caption, f);
+            }
+            Block::Footnote { ref mut elements, .. } |
+            Block::Heading { ref mut elements, .. } |
+            Block::Note { ref mut elements, .. } |
+            Block::Paragraph { ref mut elements, .. } => visit_inlines_mut(elements, f),
+            Block::NoteList { ref mut elements, .. } => {
+                for note in elements {
+                    visit_inlines_mut(&mut note.elements, f);
+                }
+            }
+            Block::OrderedList { ref mut elements, .. } |
+            Block::UnorderedList { ref mut elements, .. } => {
+                for element in elements {
+                    match *element {
+                        ListElement::ListItem { ref mut elements, .. } => visit_inlines_mut(elements, f),
+                        ListElement::List(ref mut list) => visit_inline_elements_mut(::std::slice::from_mut(list), f),
+                    }
+                }
+            }
+            Block::Table { ref mut elements, .. } => visit_table_elements_mut(elements, f),
+            _ => {}
+        }
+    }
+}
+
+/// Calls `f` for every inline element in `elements`, including nested ones.
+pub fn visit_inlines_mut<'a, F: FnMut(&mut Inline<'a>)>(elements: &mut [Inline<'a>], f: &mut F) {
+    for element in elements {
+        f(element);
+
+        match *element {
+            Inline::Bold { ref mut elements, .. } |
+            Inline::Citation { ref mut elements, .. } |
+            Inline::Italic { ref mut elements, .. } |
+            Inline::Link { ref mut elements, .. } |
+            Inline::Span { ref mut elements, .. } |
+            Inline::Strikethrough { ref mut elements, .. } |
+            Inline::Subscript { ref mut elements, .. } |
+            Inline::Superscript { ref mut elements, .. } |
+            Inline::Underlined { ref mut elements, .. } => visit_inlines_mut(elements, f),
+            _ => {}
+        }
+    }
+}
+
+fn visit_table_elements_mut<'a, F: FnMut(&mut Inline<'a>)>(elements: &mut [TableElement<'a>], f: &mut F) {
+    for element in elements {
+        match *element {
+            TableElement::Caption { ref mut elements, .. } => visit_inlines_mut(elements, f),
+            TableElement::ColGroup { .. } => {}
+            TableElement::Row { ref mut cells, .. } => {
+                for cell in cells {
+                    match *cell {
+                        TableCell::Data { ref mut elements, .. } |
+                        TableCell::Header { ref mut elements, .. } => visit_inlines_mut(elements, f),
+                    }
+                }
+            }
+            TableElement::Section { ref mut rows, .. } => visit_table_elements_mut(rows, f),
+        }
+    }
+}
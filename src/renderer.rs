@@ -1,24 +1,57 @@
+use glyphs::*;
 use into_string::*;
 use parser::*;
+use regex::Regex;
+use std::fmt;
+use std::io::{self, Write};
 use std::iter;
+use std::path::Path;
 
 /// Options for rendering Textile markup language.
+#[derive(Clone)]
 pub struct RenderOptions {
+    /// Renders comment blocks, e.g. `###. Comment`, as HTML comments. By default, they are omitted.
+    pub comments: bool,
     pub compress: bool,
+    /// Prefix of footnote and endnote ID's, e.g. `fn{prefix}1`. If it is `None`, prefix is generated
+    /// from the rendered text, so footnotes of different documents on one page don't clash.
+    pub footnote_id_prefix: Option<String>,
+    /// Typographic glyphs, e.g. curly quotes or dashes, which are enabled by `Some(Glyphs::default())`.
+    /// By default, it is `None` and text is rendered as is.
+    pub glyphs: Option<Glyphs>,
+    /// HTML5 output. Alignment of images is rendered as `align-left`, `align-center` or `align-right`
+    /// class instead of deprecated `align` attribute.
+    pub html5: bool,
+    /// Renders images. If it is `false`, images are replaced with their alternative text.
+    pub images: bool,
     pub indent: u8,
+    /// Selects glyphs for element with `lang` attribute, e.g. `p[de]. Text`, using `Glyphs::for_lang`.
+    /// Custom glyphs are kept, so only default ones are replaced.
+    pub lang_glyphs: bool,
+    /// Restricted mode for untrusted input. Raw HTML is escaped, `notextile` blocks and `==` are
+    /// disabled, classes, ID's and CSS styles are dropped, links get `rel="nofollow"` and only
+    /// `http`, `https`, `mailto` and relative URL's are allowed.
+    pub restricted: bool,
 }
 
 impl Default for RenderOptions {
     fn default() -> RenderOptions {
         RenderOptions {
+            comments: false,
             compress: false,
+            footnote_id_prefix: None,
+            glyphs: None,
+            html5: false,
+            images: true,
             indent: 2,
+            lang_glyphs: true,
+            restricted: false,
         }
     }
 }
 
 /// Renders Textile string into HTML string with default options.
-/// Accepts `&str`, `String` or `Path` data type.
+/// Accepts `&str` or `String` data type.
 ///
 /// # Example
 ///
@@ -27,11 +60,11 @@ impl Default for RenderOptions {
 /// assert_eq!(html, "<h2><strong>Heading of level 2</strong></h2>".to_string());
 /// ```
 pub fn render<S: IntoString>(text: S) -> String {
-    render_blocks(&parse(text.into_string()), &RenderOptions::default())
+    render_with(text, RenderOptions::default())
 }
 
 /// Renders Textile string into HTML string with specified options.
-/// Accepts `&str`, `String` or `Path` data type.
+/// Accepts `&str` or `String` data type.
 ///
 /// # Example
 ///
@@ -40,198 +73,746 @@ pub fn render<S: IntoString>(text: S) -> String {
 /// assert_eq!(html, "<h2><strong>Heading of level 2</strong></h2>".to_string());
 /// ```
 pub fn render_with<S: IntoString>(text: S, options: RenderOptions) -> String {
-    render_blocks(&parse(text.into_string()), &options)
+    let mut res = String::new();
+    // Writing into string never fails.
+    let _ = render_fmt(&mut res, text, options);
+    res
 }
 
-fn render_attributes(attributes: &Attributes) -> String {
-    if !attributes.is_empty() {
-        let mut res = Vec::new();
+/// Renders Textile string into HTML with specified options and writes it into `fmt::Write` writer.
+///
+/// # Example
+///
+/// ```rust
+/// let mut html = String::from("<body>");
+/// textile::render_fmt(&mut html, "*Text*", textile::RenderOptions::default()).unwrap();
+/// assert_eq!(html, "<body><p><strong>Text</strong></p>".to_string());
+/// ```
+pub fn render_fmt<W: fmt::Write, S: IntoString>(writer: &mut W, text: S, mut options: RenderOptions) -> fmt::Result {
+    let text = text.into_string();
 
-        for (key, value) in attributes {
-            res.push(format!("{}=\"{}\"", key, value));
+    if options.footnote_id_prefix.is_none() {
+        options.footnote_id_prefix = Some(format!("{:x}-", fnv_hash(&text)));
+    }
+    render_blocks(writer, &parse(text), &options)
+}
+
+/// Renders Textile string into HTML with specified options and writes it into `io::Write` writer,
+/// e.g. file or response buffer. Returns error of writer, if writing fails. Output is buffered
+/// and flushed before return, so writer doesn't need to be wrapped in `io::BufWriter`.
+///
+/// # Example
+///
+/// ```rust
+/// let mut html = Vec::new();
+/// textile::render_to(&mut html, "*Text*", textile::RenderOptions::default()).unwrap();
+/// assert_eq!(html, b"<p><strong>Text</strong></p>");
+/// ```
+pub fn render_to<W: io::Write, S: IntoString>(writer: &mut W, text: S, options: RenderOptions) -> io::Result<()> {
+    // HTML is written in small fragments, so they are buffered.
+    let mut buffer = io::BufWriter::new(writer);
+    let mut adapter = IoWriter { inner: &mut buffer, error: Ok(()) };
+
+    match render_fmt(&mut adapter, text, options) {
+        Ok(()) => buffer.flush(),
+        Err(_) => adapter.error.and(Err(io::Error::other("formatter error"))),
+    }
+}
+
+/// Renders contents of file into HTML string with specified options.
+/// Returns error, if file can't be read or isn't valid UTF-8.
+///
+/// # Example
+///
+/// ```rust
+/// let html = textile::render_file("missing.textile", textile::RenderOptions::default());
+/// assert!(html.is_err());
+/// ```
+pub fn render_file<P: AsRef<Path>>(path: P, options: RenderOptions) -> io::Result<String> {
+    read_file(path).map(|text| render_with(text, options))
+}
+
+/// Renders untrusted Textile string into HTML string in restricted mode.
+/// Accepts `&str` or `String` data type.
+///
+/// # Example
+///
+/// ```rust
+/// let html = textile::render_restricted("p{color: red}. <b>Text</b> \"Link\":javascript:void");
+/// assert_eq!(html, "<p>&lt;b&gt;Text&lt;/b&gt; Link</p>".to_string());
+/// ```
+pub fn render_restricted<S: IntoString>(text: S) -> String {
+    render_with(text, RenderOptions {
+        images: false,
+        restricted: true,
+        ..RenderOptions::default()
+    })
+}
+
+/// Adapter for writing HTML into `io::Write` writer, which keeps error of writer.
+struct IoWriter<'a, W: 'a> {
+    inner: &'a mut W,
+    error: io::Result<()>,
+}
+
+impl<'a, W: io::Write> fmt::Write for IoWriter<'a, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|err| {
+            self.error = Err(err);
+            fmt::Error
+        })
+    }
+}
+
+fn render_attributes<W: fmt::Write>(out: &mut W, attributes: &Attributes, options: &RenderOptions) -> fmt::Result {
+    // Attributes are sorted, so the same document is always rendered the same way.
+    let mut attributes: Vec<_> = attributes.iter().collect();
+    attributes.sort();
+
+    for (key, value) in attributes {
+        if options.restricted {
+            match &**key {
+                "class" | "id" | "style" => continue,
+                "cite" | "href" | "src" if !is_safe_url(value) => continue,
+                // Character references aren't allowed, because they can hide unsafe URL's.
+                _ => write!(out, " {}=\"{}\"", key, escape_code(value))?,
+            }
+        } else {
+            write!(out, " {}=\"{}\"", key, escape_html(value))?;
         }
-        format!(" {}", res.join(" "))
-    } else {
-        String::default()
     }
+    Ok(())
 }
 
-fn render_blocks(elements: &[Block], options: &RenderOptions) -> String {
-    let mut res = String::new();
+/// Checks that URL is relative or has `http`, `https` or `mailto` scheme.
+fn is_safe_url(url: &str) -> bool {
+    match url.find([':', '/', '?', '#']) {
+        Some(idx) if url[idx..].starts_with(':') => {
+            let scheme: String = url[..idx].chars()
+                .filter(|ch| !ch.is_whitespace() && !ch.is_control())
+                .collect::<String>()
+                .to_lowercase();
 
-    for (idx, element) in elements.iter().enumerate() {
-        if idx > 0 && idx < elements.len() && !options.compress {
-            res.push_str("\n");
+            scheme == "http" || scheme == "https" || scheme == "mailto"
         }
-        res.push_str(&*render_block(element, options));
+        _ => true,
+    }
+}
+
+fn render_blocks<W: fmt::Write>(out: &mut W, elements: &[Block], options: &RenderOptions) -> fmt::Result {
+    for (idx, element) in elements.iter().filter(|element| is_rendered(element, options)).enumerate() {
+        if idx > 0 && !options.compress {
+            out.write_str("\n")?;
+        }
+        render_block(out, element, options)?;
+    }
+    Ok(())
+}
+
+/// Checks that block is rendered into HTML. Omitted blocks don't leave empty lines.
+fn is_rendered(element: &Block, options: &RenderOptions) -> bool {
+    match *element {
+        Block::Comment(_) => options.comments && !options.restricted,
+        Block::Note { .. } => false,
+        _ => true,
     }
-    res
 }
 
-fn render_block(element: &Block, options: &RenderOptions) -> String {
+fn render_block<W: fmt::Write>(out: &mut W, element: &Block, options: &RenderOptions) -> fmt::Result {
+    let lang_options = get_lang_options(element.attributes(), options);
+    let options = lang_options.as_ref().unwrap_or(options);
+
     match *element {
         Block::BlockQuotation { ref attributes, ref elements } => {
+            out.write_str("<blockquote")?;
+            render_attributes(out, attributes, options)?;
+            out.write_str(">")?;
+
             if !options.compress {
-                let mut res = String::new();
-                let spaces: String = iter::repeat(" ").take(options.indent as usize).collect();
+                let spaces = " ".repeat(options.indent as usize);
 
-                for element in elements {
-                    res.push_str(&*format!("\n{}{}", spaces, render_block(element, options)));
+                for element in elements.iter().filter(|element| is_rendered(element, options)) {
+                    write!(out, "\n{}", spaces)?;
+                    render_block(out, element, options)?;
                 }
-                format!("<blockquote{}>{}\n</blockquote>",
-                        render_attributes(attributes),
-                        res)
+                out.write_str("\n</blockquote>")
             } else {
-                format!("<blockquote{}>{}</blockquote>",
-                        render_attributes(attributes),
-                        render_blocks(elements, options))
+                render_blocks(out, elements, options)?;
+                out.write_str("</blockquote>")
             }
         }
         Block::CodeBlock { ref attributes, ref code } => {
-            format!("<pre{}><code>{}</code></pre>",
-                    render_attributes(attributes),
-                    code)
+            out.write_str("<pre")?;
+            render_attributes(out, attributes, options)?;
+            write!(out, "><code>{}</code></pre>", escape_code(code))
         }
-        Block::Heading { ref attributes, level, ref elements } => {
-            format!("<h{0}{1}>{2}</h{0}>",
-                    level,
-                    render_attributes(attributes),
-                    render_inline_elements(elements, options))
+        Block::Comment(ref lines) if options.comments && !options.restricted => {
+            let mut text = lines.join("\n");
+
+            // Double hyphen isn't allowed inside HTML comment.
+            while text.contains("--") {
+                text = text.replace("--", "- -");
+            }
+            write!(out, "<!-- {} -->", text)
         }
-        Block::NoTextileBlock(ref strings) => strings.join("\n"),
-        Block::OrderedList { ref attributes, ref elements, level } => {
-            let mut res = String::new();
-            let list_item_indent: String = iter::repeat(" ").take((options.indent * (level + 1)) as usize).collect();
-            let list_indent: String = iter::repeat(" ").take((options.indent * level) as usize).collect();
+        Block::DefinitionList { ref attributes, ref elements } => {
+            out.write_str("<dl")?;
+            render_attributes(out, attributes, options)?;
+            out.write_str(">")?;
 
             for element in elements {
-                let html = match *element {
-                    ListElement::ListItem { ref attributes, ref elements } => {
-                        format!("\n{}<li{}>{}</li>",
-                                list_item_indent,
-                                render_attributes(attributes),
-                                render_inline_elements(elements, options))
-                    },
-                    ListElement::List(ref list) => {
-                        format!("\n{}", render_block(list, options))
-                    }
+                let (tag, attributes, elements) = match *element {
+                    DefinitionListElement::Definition { ref attributes, ref elements } => ("dd", attributes, elements),
+                    DefinitionListElement::Term { ref attributes, ref elements } => ("dt", attributes, elements),
                 };
-                res.push_str(&html);
+                line_break(out, options, 1)?;
+                render_inline_tag(out, tag, attributes, elements, None, options)?;
+            }
+            line_break(out, options, 0)?;
+            out.write_str("</dl>")
+        }
+        Block::Div { ref attributes, ref elements } => {
+            out.write_str("<div")?;
+            render_attributes(out, attributes, options)?;
+            out.write_str(">")?;
+
+            for element in elements.iter().filter(|element| is_rendered(element, options)) {
+                line_break(out, options, 1)?;
+                render_block(out, element, options)?;
             }
-            format!("{0}<ol{1}>{2}\n{0}</ol>",
-                    list_indent,
-                    render_attributes(attributes),
-                    res)
-        },
+            line_break(out, options, 0)?;
+            out.write_str("</div>")
+        }
+        Block::Figure { ref attributes, ref caption, ref image } => {
+            out.write_str("<figure")?;
+            render_attributes(out, attributes, options)?;
+            out.write_str(">")?;
+            line_break(out, options, 1)?;
+            render_inline_elements(out, ::std::slice::from_ref(&**image), None, options)?;
+
+            if !caption.is_empty() {
+                line_break(out, options, 1)?;
+                out.write_str("<figcaption>")?;
+                render_inline_elements(out, caption, None, options)?;
+                out.write_str("</figcaption>")?;
+            }
+            line_break(out, options, 0)?;
+            out.write_str("</figure>")
+        }
+        Block::Footnote { ref attributes, backlink, ref elements, ref id } => {
+            let class = match attributes.get("class") {
+                Some(class) if !options.restricted => escape_html(class),
+                _ => "footnote".to_string(),
+            };
+            let mut attributes = attributes.clone();
+            attributes.remove("class");
+            attributes.remove("id");
+
+            write!(out, "<p class=\"{}\" id=\"{}\"", class, footnote_id("fn", id, options))?;
+            render_attributes(out, &attributes, options)?;
+            out.write_str("><sup>")?;
+
+            if backlink {
+                write!(out, "<a href=\"#{}\">{}</a>", footnote_id("fnref", id, options), id)?;
+            } else {
+                out.write_str(id)?;
+            }
+            out.write_str("</sup> ")?;
+            render_inline_elements(out, elements, None, options)?;
+            out.write_str("</p>")
+        }
+        Block::Heading { ref attributes, level, ref elements } => {
+            render_inline_tag(out, &format!("h{}", level), attributes, elements, None, options)
+        }
+        Block::Html(ref strings) | Block::NoTextileBlock(ref strings) if options.restricted => {
+            write!(out, "<p>{}</p>", escape_html(&strings.join("\n")))
+        }
+        Block::Html(ref strings) => out.write_str(&strings.join("\n")),
+        Block::NoTextileBlock(ref strings) => out.write_str(&strings.join("\n")),
+        Block::NoteList { ref attributes, ref backlinks, ref elements, ref symbol, .. } => {
+            out.write_str("<ol")?;
+            render_attributes(out, attributes, options)?;
+            out.write_str(">")?;
+
+            for note in elements {
+                let references = match *backlinks {
+                    _ if note.number.is_none() => 0,
+                    NoteBacklinks::All => note.references,
+                    NoteBacklinks::First => 1,
+                    NoteBacklinks::Disabled => 0,
+                };
+                let mut attributes = note.attributes.clone();
+                attributes.remove("id");
+
+                line_break(out, options, 1)?;
+                write!(out, "<li id=\"{}\"", footnote_id("note", &note.label, options))?;
+                render_attributes(out, &attributes, options)?;
+                out.write_str(">")?;
+
+                for reference in 1..references + 1 {
+                    let text = match *symbol {
+                        Some(ref symbol) => escape_html(symbol),
+                        None if references == 1 => "^".to_string(),
+                        None => ((b'a' + ((reference - 1) % 26) as u8) as char).to_string(),
+                    };
+                    write!(out, "<sup><a href=\"#{}\">{}</a></sup>",
+                           footnote_id("noteref", &format!("{}-{}", note.label, reference), options),
+                           text)?;
+                }
+                if references > 0 {
+                    out.write_str(" ")?;
+                }
+                render_inline_elements(out, &note.elements, None, options)?;
+                out.write_str("</li>")?;
+            }
+            line_break(out, options, 0)?;
+            out.write_str("</ol>")
+        }
+        Block::OrderedList { ref attributes, ref elements, level } => {
+            render_list(out, "ol", attributes, elements, level, options)
+        }
         Block::Paragraph { ref attributes, ref elements, .. } => {
-            format!("<p{}>{}</p>",
-                    render_attributes(attributes),
-                    render_inline_elements(elements, options))
-        },
-        Block::Pre {ref attributes, ref lines} => {
-            format!("<pre{}>{}</pre>",
-                    render_attributes(attributes),
-                    lines.join("\n"))
-        },
-        Block::UnorderedList { ref attributes, ref elements, level } => {
-            let mut res = String::new();
-            let list_item_indent: String = iter::repeat(" ").take((options.indent * (level + 1)) as usize).collect();
-            let list_indent: String = iter::repeat(" ").take((options.indent * level) as usize).collect();
+            render_inline_tag(out, "p", attributes, elements, None, options)
+        }
+        Block::Pre { ref attributes, ref lines } => {
+            out.write_str("<pre")?;
+            render_attributes(out, attributes, options)?;
+            write!(out, ">{}</pre>", escape_html(&lines.join("\n")))
+        }
+        Block::Table { ref attributes, ref elements } => {
+            out.write_str("<table")?;
+            render_attributes(out, attributes, options)?;
+            out.write_str(">")?;
 
             for element in elements {
-                let html = match *element {
-                    ListElement::ListItem { ref attributes, ref elements } => {
-                        format!("\n{}<li{}>{}</li>",
-                                list_item_indent,
-                                render_attributes(attributes),
-                                render_inline_elements(elements, options))
-                    },
-                    ListElement::List(ref list) => {
-                        format!("\n{}", render_block(list, options))
-                    }
+                render_table_element(out, element, options, 1)?;
+            }
+            line_break(out, options, 0)?;
+            out.write_str("</table>")
+        }
+        Block::UnorderedList { ref attributes, ref elements, level } => {
+            render_list(out, "ul", attributes, elements, level, options)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn render_list<W: fmt::Write>(out: &mut W,
+                              tag: &str,
+                              attributes: &Attributes,
+                              elements: &[ListElement],
+                              level: u8,
+                              options: &RenderOptions) -> fmt::Result {
+    let list_item_indent = " ".repeat((options.indent * (level + 1)) as usize);
+    let list_indent = " ".repeat((options.indent * level) as usize);
+
+    write!(out, "{}<{}", list_indent, tag)?;
+    render_attributes(out, attributes, options)?;
+    out.write_str(">")?;
+
+    for element in elements {
+        out.write_str("\n")?;
+
+        match *element {
+            ListElement::ListItem { ref attributes, ref elements } => {
+                out.write_str(&list_item_indent)?;
+                render_inline_tag(out, "li", attributes, elements, None, options)?;
+            }
+            ListElement::List(ref list) => render_block(out, list, options)?,
+        }
+    }
+    write!(out, "\n{}</{}>", list_indent, tag)
+}
+
+fn render_table_element<W: fmt::Write>(out: &mut W, element: &TableElement, options: &RenderOptions, level: u8) -> fmt::Result {
+    match *element {
+        TableElement::Caption { ref attributes, ref elements } => {
+            line_break(out, options, level)?;
+            render_inline_tag(out, "caption", attributes, elements, None, options)
+        }
+        TableElement::ColGroup { ref attributes, ref cols } => {
+            line_break(out, options, level)?;
+            out.write_str("<colgroup")?;
+            render_attributes(out, attributes, options)?;
+            out.write_str(">")?;
+
+            for col in cols {
+                line_break(out, options, level + 1)?;
+                out.write_str("<col")?;
+                render_attributes(out, col, options)?;
+                out.write_str(">")?;
+            }
+            if !cols.is_empty() {
+                line_break(out, options, level)?;
+            }
+            out.write_str("</colgroup>")
+        }
+        TableElement::Row { ref attributes, ref cells } => {
+            line_break(out, options, level)?;
+            out.write_str("<tr")?;
+            render_attributes(out, attributes, options)?;
+            out.write_str(">")?;
+
+            for cell in cells {
+                let (tag, attributes, elements) = match *cell {
+                    TableCell::Data { ref attributes, ref elements } => ("td", attributes, elements),
+                    TableCell::Header { ref attributes, ref elements } => ("th", attributes, elements),
                 };
-                res.push_str(&html);
+                line_break(out, options, level + 1)?;
+                render_inline_tag(out, tag, attributes, elements, None, options)?;
             }
-            format!("{0}<ul{1}>{2}\n{0}</ul>",
-                    list_indent,
-                    render_attributes(attributes),
-                    res)
-        },
-        _ => "".to_string(),
+            line_break(out, options, level)?;
+            out.write_str("</tr>")
+        }
+        TableElement::Section { ref attributes, ref rows, ref tag_type } => {
+            line_break(out, options, level)?;
+            write!(out, "<{}", tag_type)?;
+            render_attributes(out, attributes, options)?;
+            out.write_str(">")?;
+
+            for row in rows {
+                render_table_element(out, row, options, level + 1)?;
+            }
+            line_break(out, options, level)?;
+            write!(out, "</{}>", tag_type)
+        }
     }
 }
 
-fn render_inline_elements(elements: &[Inline], options: &RenderOptions) -> String {
-    let mut res = String::new();
+/// Returns options with glyphs for language of element, if it has `lang` attribute and glyphs aren't custom.
+fn get_lang_options(attributes: Option<&Attributes>, options: &RenderOptions) -> Option<RenderOptions> {
+    let lang = attributes.and_then(|attrs| attrs.get("lang"))?;
+
+    match options.glyphs {
+        Some(ref glyphs) if options.lang_glyphs && is_builtin(glyphs) => {
+            Some(RenderOptions {
+                glyphs: Some(Glyphs::for_lang(lang)),
+                ..options.clone()
+            })
+        }
+        _ => None,
+    }
+}
+
+/// 32-bit FNV-1a hash. Unlike hashers of standard library, it never changes, so generated ID's are stable.
+fn fnv_hash(text: &str) -> u32 {
+    text.bytes().fold(0x811c_9dc5, |hash, byte| (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193))
+}
+
+fn footnote_id(kind: &str, id: &str, options: &RenderOptions) -> String {
+    escape_html(&format!("{}{}{}", kind, options.footnote_id_prefix.as_ref().map_or("", |prefix| prefix), id))
+}
+
+/// Escapes `&`, `<`, `>` and `"` characters in text or attribute value.
+/// Character references, e.g. `&copy;` or `&#8212;`, are left as is.
+fn escape_html(text: &str) -> String {
+    lazy_static! {
+        static ref CHAR_REFERENCE_PATTERN: Regex = Regex::new("^&(?:#[0-9]+|#[xX][0-9a-fA-F]+|[A-Za-z][A-Za-z0-9]*);").unwrap();
+    }
+    let mut res = String::with_capacity(text.len());
+
+    for (idx, ch) in text.char_indices() {
+        match ch {
+            '&' if CHAR_REFERENCE_PATTERN.is_match(&text[idx..]) => res.push('&'),
+            '&' => res.push_str("&amp;"),
+            '<' => res.push_str("&lt;"),
+            '>' => res.push_str("&gt;"),
+            '"' => res.push_str("&quot;"),
+            _ => res.push(ch),
+        }
+    }
+    res
+}
+
+/// Escapes `&`, `<`, `>` and `"` characters in code, including character references.
+fn escape_code(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Writes line break with indentation of specified level. Nothing is written, if output is compressed.
+fn line_break<W: fmt::Write>(out: &mut W, options: &RenderOptions, level: u8) -> fmt::Result {
+    if options.compress {
+        Ok(())
+    } else {
+        let mut line = String::from("\n");
+        line.extend(iter::repeat_n(' ', options.indent as usize * level as usize));
+        out.write_str(&line)
+    }
+}
+
+/// Writes element with tag, e.g. `<strong>`, containing inline elements.
+fn render_inline_tag<W: fmt::Write>(out: &mut W,
+                                    tag: &str,
+                                    attributes: &Attributes,
+                                    elements: &[Inline],
+                                    prev: Option<char>,
+                                    options: &RenderOptions) -> fmt::Result {
+    write!(out, "<{}", tag)?;
+    render_attributes(out, attributes, options)?;
+    out.write_str(">")?;
+    render_inline_elements(out, elements, prev, options)?;
+    write!(out, "</{}>", tag)
+}
+
+/// Renders inline elements. `prev` is the last character before them, which is used for typographic glyphs.
+fn render_inline_elements<W: fmt::Write>(out: &mut W,
+                                         elements: &[Inline],
+                                         mut prev: Option<char>,
+                                         options: &RenderOptions) -> fmt::Result {
 
     for element in elements {
-        let html = match *element {
+        let lang_options = get_lang_options(element.attributes(), options);
+        let options = lang_options.as_ref().unwrap_or(options);
+
+        match *element {
             Inline::Abbreviation { ref abbr, ref transcript } => {
-                format!("<acronym title=\"{}\"><span>{}</span></acronym>",
-                        transcript,
-                        abbr)
+                write!(out, "<acronym title=\"{}\"><span>{}</span></acronym>",
+                       escape_html(transcript),
+                       escape_html(abbr))?;
             }
-            Inline::Bold { ref attributes, ref elements, ref tag_type } => {
-                format!("<{0}{1}>{2}</{0}>",
-                        tag_type,
-                        render_attributes(attributes),
-                        render_inline_elements(elements, options))
+            Inline::Bold { ref attributes, ref elements, ref tag_type } |
+            Inline::Italic { ref attributes, ref elements, ref tag_type } => {
+                render_inline_tag(out, tag_type, attributes, elements, prev, options)?;
             }
-            Inline::Break => "<br>".to_string(),
+            Inline::Break => out.write_str("<br>")?,
             Inline::Citation { ref attributes, ref elements } => {
-                format!("<cite{}>{}</cite>",
-                        render_attributes(attributes),
-                        render_inline_elements(elements, options))
+                render_inline_tag(out, "cite", attributes, elements, prev, options)?;
             }
-            Inline::Code(ref text) => format!("<code>{}</code>", text),
-            Inline::Image { ref attributes, ref href } => {
-                let img = format!("<img{}>", render_attributes(attributes));
+            Inline::Code(ref text) => write!(out, "<code>{}</code>", escape_code(text))?,
+            Inline::FootnoteRef { ref id, link } => {
+                write!(out, "<sup class=\"footnote\" id=\"{}\">", footnote_id("fnref", id, options))?;
 
-                if !href.is_empty() {
-                    format!("<a href=\"{}\">{}</a>", href, img)
+                if link {
+                    write!(out, "<a href=\"#{}\">{}</a>", footnote_id("fn", id, options), id)?;
                 } else {
-                    img
+                    out.write_str(id)?;
                 }
+                out.write_str("</sup>")?;
             }
-            Inline::Italic { ref attributes, ref elements, ref tag_type } => {
-                format!("<{0}{1}>{2}</{0}>",
-                        tag_type,
-                        render_attributes(attributes),
-                        render_inline_elements(elements, options))
+            Inline::Html(ref tag) if options.restricted => out.write_str(&escape_html(tag))?,
+            Inline::Html(ref tag) => out.write_str(tag)?,
+            Inline::Image { ref attributes, .. } if !options.images ||
+                    options.restricted && !attributes.get("src").is_some_and(|src| is_safe_url(src)) => {
+                out.write_str(&escape_html(attributes.get("alt").map_or("", |alt| alt)))?;
+            }
+            Inline::Image { ref attributes, ref href } => {
+                let mut attributes = attributes.clone();
+
+                if options.html5 {
+                    if let Some(align) = attributes.remove("align") {
+                        let class = match attributes.remove("class") {
+                            Some(class) => format!("{} align-{}", class, align),
+                            None => format!("align-{}", align),
+                        };
+                        attributes.insert("class".to_string(), class);
+                    }
+                }
+                let link = !href.is_empty() && (!options.restricted || is_safe_url(href));
+
+                if link && options.restricted {
+                    write!(out, "<a href=\"{}\" rel=\"nofollow\">", escape_code(href))?;
+                } else if link {
+                    write!(out, "<a href=\"{}\">", escape_html(href))?;
+                }
+                out.write_str("<img")?;
+                render_attributes(out, &attributes, options)?;
+                out.write_str(">")?;
+
+                if link {
+                    out.write_str("</a>")?;
+                }
+            }
+            Inline::Link { ref attributes, ref elements } if options.restricted => {
+                if attributes.get("href").is_some_and(|href| is_safe_url(href)) {
+                    out.write_str("<a")?;
+                    render_attributes(out, attributes, options)?;
+                    out.write_str(" rel=\"nofollow\">")?;
+                    render_inline_elements(out, elements, prev, options)?;
+                    out.write_str("</a>")?;
+                } else {
+                    render_inline_elements(out, elements, prev, options)?;
+                }
             }
             Inline::Link { ref attributes, ref elements } => {
-                format!("<a{}>{}</a>",
-                        render_attributes(attributes),
-                        render_inline_elements(elements, options))
+                render_inline_tag(out, "a", attributes, elements, prev, options)?;
+            }
+            Inline::NoteRef { ref label, link, number, reference } => {
+                let id = footnote_id("noteref", &format!("{}-{}", label, reference), options);
+
+                if link {
+                    write!(out, "<sup><a href=\"#{}\" id=\"{}\">{}</a></sup>",
+                           footnote_id("note", label, options),
+                           id,
+                           number)?;
+                } else {
+                    write!(out, "<sup id=\"{}\">{}</sup>", id, number)?;
+                }
             }
             Inline::Span { ref attributes, ref elements } => {
-                format!("<span{}>{}</span>",
-                        render_attributes(attributes),
-                        render_inline_elements(elements, options))
+                render_inline_tag(out, "span", attributes, elements, prev, options)?;
             }
             Inline::Strikethrough { ref attributes, ref elements } => {
-                format!("<del{}>{}</del>",
-                        render_attributes(attributes),
-                        render_inline_elements(elements, options))
+                render_inline_tag(out, "del", attributes, elements, prev, options)?;
             }
             Inline::Subscript { ref attributes, ref elements } => {
-                format!("<sub{}>{}</sub>",
-                        render_attributes(attributes),
-                        render_inline_elements(elements, options))
+                render_inline_tag(out, "sub", attributes, elements, prev, options)?;
             }
             Inline::Superscript { ref attributes, ref elements } => {
-                format!("<sup{}>{}</sup>",
-                        render_attributes(attributes),
-                        render_inline_elements(elements, options))
+                render_inline_tag(out, "sup", attributes, elements, prev, options)?;
+            }
+            Inline::NoTextile(ref text) if options.restricted => out.write_str(&escape_html(text))?,
+            Inline::NoTextile(ref text) => out.write_str(text)?,
+            Inline::Text(ref text) => {
+                match options.glyphs {
+                    Some(ref glyphs) => out.write_str(&escape_html(&replace_glyphs(text, prev, glyphs)))?,
+                    None => out.write_str(&escape_html(text))?,
+                }
             }
-            Inline::Text(ref text) => text.to_string(),
             Inline::Underlined { ref attributes, ref elements } => {
-                format!("<ins{}>{}</ins>",
-                        render_attributes(attributes),
-                        render_inline_elements(elements, options))
+                render_inline_tag(out, "ins", attributes, elements, prev, options)?;
             }
+        }
+        prev = match *element {
+            Inline::Break => Some('\n'),
+            Inline::Text(ref text) => text.chars().last().or(prev),
+            // Other elements are treated as word, e.g. for closing quote after bold text.
+            _ => Some('a'),
         };
-        res.push_str(&html);
     }
-    res
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_html_correctly() {
+        assert_eq!(escape_html("<b>\"Tom & Jerry\"</b> &copy; &#8212; &#x2014;"),
+                   "&lt;b&gt;&quot;Tom &amp; Jerry&quot;&lt;/b&gt; &copy; &#8212; &#x2014;");
+        assert_eq!(escape_code("<b>&copy;</b>"), "&lt;b&gt;&amp;copy;&lt;/b&gt;");
+    }
+
+    #[test]
+    fn checks_safe_urls_correctly() {
+        assert!(is_safe_url("http://example.com"));
+        assert!(is_safe_url("MAILTO:user@example.com"));
+        assert!(is_safe_url("/path/to:page"));
+        assert!(is_safe_url("image.png?a=b:c"));
+        assert!(!is_safe_url("javascript:alert(1)"));
+        assert!(!is_safe_url(" Java\tScript:alert(1)"));
+        assert!(!is_safe_url("data:text/html,<b>"));
+    }
+
+    #[test]
+    fn renders_restricted_correctly() {
+        assert_eq!(
+            render_restricted("p(class#id){color: red}[en]. \"(link)Link\":http://example.com <i>x</i> ==<b>y</b>== !image.png(Image)!"),
+            "<p lang=\"en\"><a href=\"http://example.com\" rel=\"nofollow\">Link</a> &lt;i&gt;x&lt;/i&gt; &lt;b&gt;y&lt;/b&gt; Image</p>".to_string()
+        );
+        assert_eq!(
+            render_restricted("notextile. <script>alert(1)</script>"),
+            "<p>&lt;script&gt;alert(1)&lt;/script&gt;</p>".to_string()
+        );
+    }
+
+    #[test]
+    fn renders_glyphs_correctly() {
+        let options = || RenderOptions { glyphs: Some(Glyphs::default()), ..RenderOptions::default() };
+
+        assert_eq!(render("\"Quote\" -- (c)"), "<p>&quot;Quote&quot; -- (c)</p>".to_string());
+        // Quote inside phrase is closed by text before it.
+        assert_eq!(
+            render_with("\"Quote[*\"*]", options()),
+            "<p>&#8220;Quote<strong>&#8221;</strong></p>".to_string()
+        );
+    }
+
+    #[test]
+    fn renders_glyphs_for_lang_correctly() {
+        assert_eq!(
+            render_with("p[de]. \"Zitat\" %[en]\"quote\"%", RenderOptions { glyphs: Some(Glyphs::default()), ..RenderOptions::default() }),
+            "<p lang=\"de\">&#8222;Zitat&#8220; <span lang=\"en\">&#8220;quote&#8221;</span></p>".to_string()
+        );
+        assert_eq!(
+            render_with("\"Quote\"", RenderOptions { glyphs: Some(Glyphs::for_lang("ru")), ..RenderOptions::default() }),
+            "<p>&#171;Quote&#187;</p>".to_string()
+        );
+
+        let glyphs = Glyphs { quote_double_open: "&#8250;".to_string(), ..Glyphs::default() };
+        assert_eq!(
+            render_with("p[de]. \"Zitat\"", RenderOptions { glyphs: Some(glyphs), ..RenderOptions::default() }),
+            "<p lang=\"de\">&#8250;Zitat&#8221;</p>".to_string()
+        );
+    }
+
+    #[test]
+    fn renders_figure_and_html5_images_correctly() {
+        assert_eq!(
+            render("fig. !image.png! Caption"),
+            "<figure>\n  <img src=\"image.png\">\n  <figcaption>Caption</figcaption>\n</figure>".to_string()
+        );
+
+        let html = render_with("!<image.png!", RenderOptions { html5: true, ..RenderOptions::default() });
+        assert!(html.contains(" class=\"align-left\""));
+        assert!(!html.contains(" align="));
+    }
+
+    #[test]
+    fn renders_div_and_comments_correctly() {
+        assert_eq!(
+            render("div. Text\n\n###. Comment --> here"),
+            "<div>\n  <p>Text</p>\n</div>".to_string()
+        );
+        assert_eq!(
+            render("###. Comment\n\np. Text\n\n###. Comment\n\np. Text"),
+            "<p>Text</p>\n<p>Text</p>".to_string()
+        );
+        assert_eq!(
+            render_with("###. Comment --> here", RenderOptions { comments: true, ..RenderOptions::default() }),
+            "<!-- Comment - -> here -->".to_string()
+        );
+    }
+
+    #[test]
+    fn renders_into_writers_correctly() {
+        struct FailingWriter;
+
+        impl io::Write for FailingWriter {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut html = Vec::new();
+        render_to(&mut html, "h1. Heading\n\n* Item", RenderOptions::default()).unwrap();
+        assert_eq!(String::from_utf8(html).unwrap(), render("h1. Heading\n\n* Item"));
+
+        let err = render_to(&mut FailingWriter, "Text", RenderOptions::default()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    }
+
+    #[test]
+    fn generates_stable_footnote_ids() {
+        assert_eq!(fnv_hash(""), 0x811c_9dc5);
+        assert_eq!(
+            render("Text[1]"),
+            "<p>Text<sup class=\"footnote\" id=\"fnref7b0754d5-1\"><a href=\"#fn7b0754d5-1\">1</a></sup></p>".to_string()
+        );
+    }
+
+    #[test]
+    fn renders_escaped_text_and_code_correctly() {
+        assert_eq!(
+            render("Tom & Jerry @<script>@ ==<b>Bold</b>=="),
+            "<p>Tom &amp; Jerry <code>&lt;script&gt;</code> <b>Bold</b></p>".to_string()
+        );
+    }
 }
@@ -0,0 +1,870 @@
+//! Writer of parsed elements back into Textile markup.
+
+use glyphs::Glyphs;
+use into_string::*;
+use parser::*;
+use renderer::{render_with, RenderOptions};
+use std::iter;
+
+/// Writes block elements into Textile markup, which is parsed into the same elements.
+/// Endnotes collected into lists of notes are written after other blocks. URL's of resolved
+/// link aliases, which can't be written in place, e.g. with parentheses, are written as aliases again.
+///
+/// # Example
+///
+/// ```rust
+/// use textile::parser::parse;
+///
+/// let blocks = parse("p(intro). Some **bold** text\n\n* Item");
+/// let markup = textile::to_textile(&blocks);
+/// assert_eq!(markup, "p(intro). Some **bold** text\n\n* Item\n");
+/// assert_eq!(parse(markup), blocks);
+/// ```
+pub fn to_textile(blocks: &[Block]) -> String {
+    write_textile(blocks, None)
+}
+
+/// Formats Textile string into canonical markup, which is rendered into the same HTML. Blank lines
+/// are collapsed, signatures, markers of lists and attributes are written in the same order and form,
+/// and lines of text are rewrapped to column limit. `*` and `**` are both kept, since they are rendered
+/// into `strong` and `b` elements. If formatted markup would be rendered differently, text is returned as is.
+///
+/// Footnote ID's generated from text differ after formatting, unless `footnote_id_prefix` is set.
+///
+/// # Example
+///
+/// ```rust
+/// let markup = textile::format("p((. Some text with *bold*\n\n\n\n*  Item", 16);
+/// assert_eq!(markup, "p((. Some text\n with *bold*\n\n* Item\n");
+/// ```
+pub fn format<S: IntoString>(text: S, width: usize) -> String {
+    let text = text.into_string();
+    // Glyphs are compared too, so line breaks don't change them.
+    let options = || RenderOptions {
+        comments: true,
+        footnote_id_prefix: Some(String::new()),
+        glyphs: Some(Glyphs::default()),
+        ..RenderOptions::default()
+    };
+    let html = render_with(&*text, options());
+    let blocks = parse(&*text);
+
+    // Rewrapped lines may be parsed differently, e.g. `=:` at the end of line closes definition.
+    for width in [Some(width), None] {
+        let res = write_textile(&blocks, width);
+
+        if render_with(&*res, options()) == html {
+            return res;
+        }
+    }
+    text
+}
+
+fn write_textile(blocks: &[Block], width: Option<usize>) -> String {
+    let mut writer = Writer { aliases: Vec::new(), width, column: 0 };
+    let mut text = writer.write_blocks(blocks);
+    let mut notes = Vec::new();
+    collect_notes(blocks, &mut notes);
+
+    for note in notes {
+        if !text.is_empty() {
+            separate(&mut text);
+        }
+        text.push_str(&writer.write_note(&note.attributes, &note.label, &note.elements));
+    }
+
+    // Lines of aliases are parsed as empty lines, so they don't change blocks at the start of document.
+    let mut res = String::new();
+    for (idx, href) in writer.aliases.iter().enumerate() {
+        res.push_str(&format!("[{}]{}\n", alias_name(idx), href));
+    }
+    if !res.is_empty() {
+        res.push('\n');
+    }
+
+    if !text.is_empty() {
+        res.push_str(&text);
+        // Carriage return before line feed would be removed with it.
+        if !text.ends_with('\r') {
+            res.push('\n');
+        }
+    }
+    res
+}
+
+/// Kind of element, which attributes are written. Kinds differ in supported shorthands.
+#[derive(Clone, Copy, PartialEq)]
+enum Target {
+    /// Block, e.g. `p((=. Text` with padding and alignment.
+    Block,
+    /// Inline element or list item, e.g. `*(class)[en]Text*`.
+    Inline,
+    /// Table row, section or cell, e.g. `|\2=^. Cell|` with spans and alignments.
+    Table,
+    /// Table column, e.g. `|:\2(class). 100|` with span.
+    Column,
+}
+
+struct Writer {
+    /// URL's, which are written as link aliases.
+    aliases: Vec<String>,
+    /// Column limit, which lines of text are rewrapped to. If it is `None`, lines are kept.
+    width: Option<usize>,
+    /// Column of the current line, which text of block starts at.
+    column: usize,
+}
+
+impl Writer {
+    /// Writes text of block after its signature.
+    fn write_text(&mut self, prefix: &str, elements: &[Inline]) -> String {
+        self.column = prefix.chars().count();
+        format!("{}{}", prefix, self.write_inline_elements(elements, true))
+    }
+
+    fn write_blocks(&mut self, elements: &[Block]) -> String {
+        let mut res = String::new();
+
+        let mut extended = false;
+
+        for (idx, element) in elements.iter().enumerate() {
+            if idx > 0 {
+                separate(&mut res);
+            }
+            let text = match *element {
+                // Extended block ends only before line, which is parsed as block alone.
+                // Definition list in `; Term` form needs the next line, so `- Term :=` form is written.
+                Block::DefinitionList { ref elements, .. } if extended => self.write_definition_list(elements, true),
+                ref element => self.write_block(element),
+            };
+            extended = match *element {
+                Block::BlockQuotation { .. } | Block::CodeBlock { .. } | Block::Comment(_) | Block::Div { .. } |
+                Block::NoTextileBlock(_) | Block::Pre { .. } => {
+                    text.split(' ').next().is_some_and(|signature| signature.ends_with("..") || signature.contains("..:"))
+                }
+                _ => false,
+            };
+            res.push_str(&text);
+        }
+        res
+    }
+
+    fn write_block(&mut self, element: &Block) -> String {
+        match *element {
+            Block::BlockQuotation { ref attributes, ref elements } => {
+                let mut attributes = attributes.clone();
+                let cite = attributes.remove("cite").map_or_else(String::new, |cite| format!(":{}", cite));
+                // Paragraphs of block quotation share its attributes, so only `p.` signature is written.
+                let mode = match elements[..] {
+                    [Block::Paragraph { starts_with_p: false, .. }] => ".",
+                    _ => "..",
+                };
+                let signature = format!("bq{}{}{} ", write_attributes(&attributes, Target::Block), mode, cite);
+                let paragraphs: Vec<String> = elements.iter()
+                    .enumerate()
+                    .map(|(idx, element)| {
+                        let prefix = if idx == 0 { &*signature } else { "" };
+
+                        match *element {
+                            Block::Paragraph { ref elements, starts_with_p, .. } => {
+                                self.write_text(&format!("{}{}", prefix, if starts_with_p { "p. " } else { "" }), elements)
+                            }
+                            ref element => format!("{}{}", prefix, self.write_block(element)),
+                        }
+                    })
+                    .collect();
+                paragraphs.join("\n\n")
+            }
+            Block::CodeBlock { ref attributes, ref code } => {
+                let lines: Vec<&str> = code.split('\n').collect();
+                format!("bc{}{} {}", write_attributes(attributes, Target::Block), lines_mode(&lines), code)
+            }
+            Block::Comment(ref lines) => format!("###{} {}", lines_mode(lines), lines.join("\n")),
+            Block::DefinitionList { ref elements, .. } => self.write_definition_list(elements, false),
+            Block::Div { ref attributes, ref elements } => {
+                let text = self.write_blocks(elements);
+                let lines: Vec<&str> = text.split('\n').collect();
+                format!("div{}{} {}", write_attributes(attributes, Target::Block), lines_mode(&lines), text)
+            }
+            Block::Figure { ref attributes, ref caption, ref image } => {
+                let prefix = format!("fig{}. {}", write_attributes(attributes, Target::Block), self.write_inline(image));
+
+                if caption.is_empty() {
+                    prefix
+                } else {
+                    self.write_text(&(prefix + " "), caption)
+                }
+            }
+            Block::Footnote { ref attributes, backlink, ref elements, ref id } => {
+                let prefix = format!("fn{}{}{}. ", id, if backlink { "^" } else { "" }, write_attributes(attributes, Target::Block));
+                self.write_text(&prefix, elements)
+            }
+            Block::Heading { ref attributes, ref elements, level } => {
+                let prefix = format!("h{}{}. ", level, write_attributes(attributes, Target::Block));
+                self.write_text(&prefix, elements)
+            }
+            Block::Html(ref lines) => lines.join("\n"),
+            Block::NoTextileBlock(ref lines) => format!("notextile{} {}", lines_mode(lines), lines.join("\n")),
+            Block::Note { ref attributes, ref elements, ref label } => self.write_note(attributes, label, elements),
+            Block::NoteList { ref attributes, ref backlinks, ref symbol, unreferenced, .. } => {
+                format!("notelist{}{}{}{}.",
+                        write_attributes(attributes, Target::Block),
+                        symbol.as_ref().map_or_else(String::new, |symbol| format!(":{}", symbol)),
+                        match *backlinks {
+                            NoteBacklinks::All => "",
+                            NoteBacklinks::First => "^",
+                            NoteBacklinks::Disabled => "!",
+                        },
+                        if unreferenced { "+" } else { "" })
+            }
+            Block::OrderedList { .. } | Block::UnorderedList { .. } => {
+                let mut lines = Vec::new();
+                self.write_list(element, &mut lines);
+                lines.join("\n")
+            }
+            Block::Paragraph { ref attributes, ref elements, starts_with_p } => {
+                let prefix = if starts_with_p || !attributes.is_empty() {
+                    format!("p{}. ", write_attributes(attributes, Target::Block))
+                } else {
+                    String::new()
+                };
+                self.write_text(&prefix, elements)
+            }
+            Block::Pre { ref attributes, ref lines } => {
+                format!("pre{}{} {}", write_attributes(attributes, Target::Block), lines_mode(lines), lines.join("\n"))
+            }
+            Block::Table { ref attributes, ref elements } => {
+                let mut lines = Vec::new();
+
+                if !attributes.is_empty() {
+                    lines.push(format!("table{}.", write_attributes(attributes, Target::Block)));
+                }
+                for element in elements {
+                    self.write_table_element(element, &mut lines);
+                }
+                lines.join("\n")
+            }
+        }
+    }
+
+    fn write_note(&mut self, attributes: &Attributes, label: &str, elements: &[Inline]) -> String {
+        let prefix = format!("note#{}{}. ", label, write_attributes(attributes, Target::Block));
+        self.write_text(&prefix, elements)
+    }
+
+    fn write_list(&mut self, list: &Block, lines: &mut Vec<String>) {
+        let (elements, level, marker) = match *list {
+            Block::OrderedList { ref elements, level, .. } => (elements, level, "#"),
+            Block::UnorderedList { ref elements, level, .. } => (elements, level, "*"),
+            _ => return,
+        };
+        let marker = marker.repeat(level as usize + 1);
+
+        for element in elements {
+            match *element {
+                ListElement::ListItem { ref attributes, ref elements } => {
+                    let prefix = format!("{}{} ", marker, write_attributes(attributes, Target::Inline));
+                    let mut text = self.write_text(&prefix, elements);
+
+                    // Text starting with space is parsed only from continuation line of empty item.
+                    if text[prefix.len()..].starts_with(' ') {
+                        text.insert(prefix.len(), '\n');
+                    }
+                    lines.push(text);
+                }
+                ListElement::List(ref list) => self.write_list(list, lines),
+            }
+        }
+    }
+
+    fn write_definition_list(&mut self, elements: &[DefinitionListElement], dashes: bool) -> String {
+        let items: Vec<(bool, &Attributes, String)> = elements.iter()
+            .map(|element| match *element {
+                DefinitionListElement::Term { ref attributes, ref elements } => {
+                    (true, attributes, self.write_text("", elements))
+                }
+                DefinitionListElement::Definition { ref attributes, ref elements } => {
+                    (false, attributes, self.write_text("", elements))
+                }
+            })
+            .collect();
+        let mut lines = Vec::new();
+
+        // Only definition in `- Term :=` form, which ends with `=:`, may contain empty lines or end with line break.
+        if dashes || items.iter().any(|(_, _, text)| text.contains("\n\n") || text.ends_with('\n')) {
+            let mut items = items.iter().peekable();
+
+            while let Some(&(_, attributes, ref term)) = items.next() {
+                let mut line = format!("-{} {}", write_attributes(attributes, Target::Inline), term);
+
+                if let Some(&&(false, _, ref definition)) = items.peek() {
+                    items.next();
+
+                    if definition.contains('\n') {
+                        line.push_str(&format!(" :=\n{} =:", definition));
+                    } else {
+                        line.push_str(&format!(" := {}", definition));
+                    }
+                }
+                lines.push(line);
+            }
+        } else {
+            for &(is_term, attributes, ref text) in &items {
+                let head = format!("{}{} ", if is_term { ";" } else { ":" }, write_attributes(attributes, Target::Inline));
+
+                // Spaces after signature are skipped, so text starting with them is moved to the next line.
+                if text.starts_with(' ') {
+                    lines.push(format!("{}\n{}", head, text));
+                } else {
+                    lines.push(head + text);
+                }
+            }
+        }
+        lines.join("\n")
+    }
+
+    fn write_table_element(&mut self, element: &TableElement, lines: &mut Vec<String>) {
+        match *element {
+            TableElement::Caption { ref attributes, ref elements } => {
+                lines.push(format!("|={}. {}", write_attributes(attributes, Target::Block), self.write_inline_elements(elements, false)));
+            }
+            TableElement::ColGroup { ref attributes, ref cols } => {
+                let specs: Vec<String> = iter::once(attributes).chain(cols)
+                    .map(|attributes| {
+                        let spec = write_attributes(attributes, Target::Column);
+
+                        match attributes.get("width") {
+                            Some(width) if spec.is_empty() => width.to_string(),
+                            Some(width) => format!("{}. {}", spec, width),
+                            None => spec,
+                        }
+                    })
+                    .collect();
+                lines.push(format!("|:{}|", specs.join("|")));
+            }
+            TableElement::Row { ref attributes, ref cells } => {
+                let cells: Vec<String> = cells.iter()
+                    .map(|cell| match *cell {
+                        TableCell::Header { ref attributes, ref elements } => {
+                            format!("_{}. {}", write_attributes(attributes, Target::Table), self.write_inline_elements(elements, false))
+                        }
+                        TableCell::Data { ref attributes, ref elements } if !attributes.is_empty() => {
+                            format!("{}. {}", write_attributes(attributes, Target::Table), self.write_inline_elements(elements, false))
+                        }
+                        TableCell::Data { ref elements, .. } => self.write_inline_elements(elements, false),
+                    })
+                    .collect();
+                let attributes = write_attributes(attributes, Target::Table);
+
+                if attributes.is_empty() {
+                    lines.push(format!("|{}|", cells.join("|")));
+                } else {
+                    lines.push(format!("{}. |{}|", attributes, cells.join("|")));
+                }
+            }
+            TableElement::Section { ref attributes, ref rows, ref tag_type } => {
+                let signature = match &**tag_type {
+                    "thead" => "^",
+                    "tfoot" => "~",
+                    _ => "-",
+                };
+                lines.push(format!("|{}{}.", signature, write_attributes(attributes, Target::Table)));
+
+                for row in rows {
+                    self.write_table_element(row, lines);
+                }
+            }
+        }
+    }
+
+    /// Writes inline elements. Unlike elements of phrase, elements of block may continue on the next line.
+    fn write_inline_elements(&mut self, elements: &[Inline], multiline: bool) -> String {
+        let written: Vec<String> = elements.iter().map(|element| self.write_inline(element)).collect();
+        let mut bracketed = Vec::new();
+        let mut res = self.join_inline_elements(elements, &written, multiline, &[], &mut bracketed);
+
+        // Brackets may change parsing too, e.g. of element containing `]`, so they are kept only if needed.
+        let mut plain = Vec::new();
+        for idx in bracketed {
+            plain.push(idx);
+            let text = self.join_inline_elements(elements, &written, multiline, &plain, &mut Vec::new());
+
+            if join_texts(&parse_inline_elements(&text.split('\n').collect::<Vec<_>>())) == join_texts(elements) {
+                res = text;
+            } else {
+                plain.pop();
+            }
+        }
+        res
+    }
+
+    /// Joins written inline elements. Elements, which need brackets, are written in them, except `plain` ones.
+    /// Indexes of elements written in brackets are added to `bracketed`.
+    fn join_inline_elements(&self,
+                            elements: &[Inline],
+                            written: &[String],
+                            multiline: bool,
+                            plain: &[usize],
+                            bracketed: &mut Vec<usize>) -> String {
+        let mut res = String::new();
+
+        for (idx, element) in elements.iter().enumerate() {
+            let prev = res.chars().last().filter(|&ch| ch != '\n');
+            let next = written.get(idx + 1).map_or("", |text| &**text);
+
+            match *element {
+                Inline::Text(ref text) if multiline && self.width.is_some() => {
+                    let mut word = String::new();
+
+                    for text in &written[idx + 1..] {
+                        match text.find([' ', '\n']) {
+                            Some(pos) => {
+                                word.push_str(&text[..pos]);
+                                break;
+                            }
+                            None => word.push_str(text),
+                        }
+                    }
+                    self.wrap_text(&mut res, text, &word);
+                    continue;
+                }
+                // Texts on one line are parsed as one text, unless the first one is phrase with mismatched delimiters.
+                // Line starting with space continues the previous one without line break.
+                Inline::Text(ref text) if multiline && idx > 0 && text.starts_with(' ') => {
+                    if let Inline::Text(_) = elements[idx - 1] {
+                        res.push('\n');
+                    }
+                }
+                // Element in square brackets may be placed inside word or next to delimiters.
+                ref element if !plain.contains(&idx) && needs_brackets(element, prev, next) => {
+                    res.push_str(&format!("[{}]", written[idx]));
+                    bracketed.push(idx);
+                    continue;
+                }
+                _ => {}
+            }
+            res.push_str(&written[idx]);
+        }
+        res
+    }
+
+    /// Appends text, which is broken before spaces exceeding column limit. Line starting with space
+    /// continues the previous one without line break, so text of adjacent lines is joined.
+    /// The last word of text is continued by `next_word` of the following elements.
+    fn wrap_text(&self, res: &mut String, text: &str, next_word: &str) {
+        let width = self.width.unwrap_or(usize::MAX);
+        let next_width = next_word.chars().count();
+        let mut start = 0;
+
+        for idx in text.match_indices(' ').map(|(idx, _)| idx).chain(iter::once(text.len())) {
+            let word = &text[start..idx];
+            let word_width = word.chars().count() + if idx == text.len() { next_width } else { 0 };
+            let column = match res.rfind('\n') {
+                Some(pos) => res[pos + 1..].chars().count(),
+                None => self.column + res.chars().count(),
+            };
+
+            if word.starts_with(' ') && column + word_width > width &&
+               res.chars().last().is_some_and(|ch| !ch.is_whitespace() && !is_glyph_around(ch, &word[1..])) {
+                res.push('\n');
+            }
+            res.push_str(word);
+            start = idx;
+        }
+    }
+
+    fn write_inline(&mut self, element: &Inline) -> String {
+        match *element {
+            Inline::Abbreviation { ref abbr, ref transcript } => format!("{}({})", abbr, transcript),
+            Inline::Bold { ref attributes, ref elements, ref tag_type } => {
+                let delimiter = if tag_type == "b" { "**" } else { "*" };
+                self.write_phrase(delimiter, attributes, elements)
+            }
+            Inline::Break => "\n".to_string(),
+            Inline::Citation { ref attributes, ref elements } => self.write_phrase("??", attributes, elements),
+            Inline::Code(ref code) => format!("@{}@", code),
+            Inline::FootnoteRef { ref id, link } => format!("[{}]{}", id, if link { "" } else { "!" }),
+            Inline::Html(ref html) => html.to_string(),
+            Inline::Image { ref attributes, ref href } => {
+                let mut res = "!".to_string();
+                res.push_str(match attributes.get("align").map(|align| &**align) {
+                    Some("left") => "<",
+                    Some("center") => "=",
+                    Some("right") => ">",
+                    _ => "",
+                });
+                let src = attributes.get("src").map_or("", |src| &**src);
+                // Source, which starts with character of alignment, is separated from `!` by attributes.
+                let is_separated = res.len() > 1 || !src.starts_with(['<', '>', '=', '|']);
+                res.push_str(&write_inline_attributes(attributes, src.is_empty() || !is_separated));
+                res.push_str(src);
+
+                if let (Some(width), Some(height)) = (attributes.get("width"), attributes.get("height")) {
+                    res.push_str(&format!(" {}x{}", width, height));
+                }
+                if let Some(alt) = attributes.get("alt") {
+                    res.push_str(&format!("({})", alt));
+                }
+                res.push('!');
+
+                if !href.is_empty() {
+                    res.push(':');
+                    res.push_str(&self.write_href(href));
+                }
+                res
+            }
+            Inline::Italic { ref attributes, ref elements, ref tag_type } => {
+                let delimiter = if tag_type == "i" { "__" } else { "_" };
+                self.write_phrase(delimiter, attributes, elements)
+            }
+            Inline::Link { ref attributes, ref elements } => {
+                let href = attributes.get("href").map_or("", |href| &**href);
+                let written_href = self.write_href(href);
+                let mut res = "\"".to_string();
+                res.push_str(&write_inline_attributes(attributes, elements.is_empty()));
+
+                // Text of link may be replaced by `$`, unless URL is written as alias.
+                match elements[..] {
+                    [Inline::Text(ref text)] if written_href == href && *text == describe_url(href) => res.push('$'),
+                    _ => res.push_str(&self.write_inline_elements(elements, false)),
+                }
+
+                if let Some(title) = attributes.get("title") {
+                    res.push_str(&format!("({})", title));
+                }
+                res.push_str("\":");
+                res.push_str(&written_href);
+                res
+            }
+            Inline::NoTextile(ref text) => format!("=={}==", text),
+            Inline::NoteRef { ref label, link, .. } => format!("[#{}{}]", label, if link { "" } else { "!" }),
+            Inline::Span { .. } if abbreviation(element).is_some() => abbreviation(element).unwrap_or("").to_string(),
+            Inline::Span { ref attributes, ref elements } => self.write_phrase("%", attributes, elements),
+            Inline::Strikethrough { ref attributes, ref elements } => self.write_phrase("-", attributes, elements),
+            Inline::Subscript { ref attributes, ref elements } => self.write_phrase("~", attributes, elements),
+            Inline::Superscript { ref attributes, ref elements } => self.write_phrase("^", attributes, elements),
+            Inline::Text(ref text) => text.to_string(),
+            Inline::Underlined { ref attributes, ref elements } => self.write_phrase("+", attributes, elements),
+        }
+    }
+
+    fn write_phrase(&mut self, delimiter: &str, attributes: &Attributes, elements: &[Inline]) -> String {
+        format!("{}{}{}{}",
+                delimiter,
+                write_inline_attributes(attributes, elements.is_empty()),
+                self.write_inline_elements(elements, false),
+                delimiter)
+    }
+
+    /// Returns URL of link or name of alias, if URL contains characters, which end it.
+    fn write_href(&mut self, href: &str) -> String {
+        if !href.contains([' ', '(', ')']) {
+            return href.to_string();
+        }
+
+        let idx = match self.aliases.iter().position(|alias| alias == href) {
+            Some(idx) => idx,
+            None => {
+                self.aliases.push(href.to_string());
+                self.aliases.len() - 1
+            }
+        };
+        alias_name(idx)
+    }
+}
+
+/// Returns attributes of inline element. Element without content is parsed only from brackets,
+/// which aren't attributes, e.g. `%[ ]%`.
+fn write_inline_attributes(attributes: &Attributes, is_empty: bool) -> String {
+    match write_attributes(attributes, Target::Inline) {
+        ref attrs if attrs.is_empty() && is_empty => "[ ]".to_string(),
+        attrs => attrs,
+    }
+}
+
+fn write_attributes(attributes: &Attributes, target: Target) -> String {
+    let mut res = String::new();
+
+    match target {
+        Target::Table => {
+            if let Some(colspan) = attributes.get("colspan") {
+                res.push_str(&format!("\\{}", colspan));
+            }
+            if let Some(rowspan) = attributes.get("rowspan") {
+                res.push_str(&format!("/{}", rowspan));
+            }
+        }
+        Target::Column => {
+            if let Some(span) = attributes.get("span") {
+                res.push_str(&format!("\\{}", span));
+            }
+        }
+        _ => {}
+    }
+
+    match (attributes.get("class"), attributes.get("id")) {
+        (None, None) => {}
+        (class, id) => {
+            res.push('(');
+            res.push_str(class.map_or("", |class| &**class));
+            if let Some(id) = id {
+                res.push('#');
+                res.push_str(id);
+            }
+            res.push(')');
+        }
+    }
+
+    if let Some(lang) = attributes.get("lang") {
+        res.push_str(&format!("[{}]", lang));
+    }
+
+    // Padding and alignments are parsed after CSS properties, so only the last properties are written as shorthands.
+    let mut props: Vec<&str> = attributes.get("style")
+        .map_or_else(Vec::new, |style| style.split("; ").filter(|prop| !prop.is_empty()).collect());
+    let vertical_align = match target {
+        Target::Table => pop_shorthand(&mut props, |prop| match prop {
+            "vertical-align: top" => Some("^".to_string()),
+            "vertical-align: bottom" => Some("~".to_string()),
+            "vertical-align: middle" => Some("-".to_string()),
+            _ => None,
+        }),
+        _ => String::new(),
+    };
+    let align = match target {
+        Target::Block | Target::Table => pop_shorthand(&mut props, |prop| match prop {
+            "text-align: left" => Some("<".to_string()),
+            "text-align: right" => Some(">".to_string()),
+            "text-align: center" => Some("=".to_string()),
+            "text-align: justify" => Some("<>".to_string()),
+            _ => None,
+        }),
+        _ => String::new(),
+    };
+    // Parentheses of padding aren't allowed in attributes of table cells.
+    let padding = match target {
+        Target::Block => pop_shorthand(&mut props, |prop| {
+            let (side, size) = prop.split_at(prop.find(": ")?);
+            let size: usize = size[2..].strip_suffix("em")?.parse().ok().filter(|&size| size > 0)?;
+
+            match side {
+                "padding-left" => Some("(".repeat(size)),
+                "padding-right" => Some(")".repeat(size)),
+                _ => None,
+            }
+        }),
+        _ => String::new(),
+    };
+
+    if !props.is_empty() {
+        res.push_str(&format!("{{{}}}", props.join("; ")));
+    }
+    res + &padding + &align + &vertical_align
+}
+
+/// Removes the last CSS property, if it has shorthand, and returns the shorthand.
+fn pop_shorthand<F: Fn(&str) -> Option<String>>(props: &mut Vec<&str>, shorthand: F) -> String {
+    match props.last().and_then(|prop| shorthand(prop)) {
+        Some(res) => {
+            props.pop();
+            res
+        }
+        None => String::new(),
+    }
+}
+
+/// Checks that element must be written in square brackets, so it isn't parsed as part of adjacent text.
+fn needs_brackets(element: &Inline, prev: Option<char>, next: &str) -> bool {
+    let is_word = |ch: Option<char>| ch.is_some_and(char::is_alphanumeric);
+    let ends_url = |text: &str| text.is_empty() || text.starts_with([' ', '(', ')', '\n']);
+    let delimiter = match *element {
+        // Transcript of abbreviation is optional.
+        Inline::Abbreviation { .. } => return prev.is_some_and(char::is_uppercase),
+        Inline::Span { .. } if abbreviation(element).is_some() => return prev.is_some_and(char::is_uppercase),
+        Inline::Bold { .. } => '*',
+        Inline::Citation { .. } => '?',
+        Inline::Code(_) => return is_word(prev) || is_word(next.chars().next()),
+        Inline::FootnoteRef { link, .. } => return link && next.starts_with('!'),
+        Inline::Image { ref href, .. } if href.is_empty() => return next.strip_prefix(':').is_some_and(|rest| !ends_url(rest)),
+        Inline::Image { .. } | Inline::Link { .. } => return !ends_url(next),
+        Inline::Italic { .. } => '_',
+        Inline::NoTextile(_) => return prev == Some('=') || next.starts_with('='),
+        Inline::Span { .. } => '%',
+        Inline::Strikethrough { .. } => '-',
+        Inline::Subscript { .. } => '~',
+        Inline::Superscript { .. } => '^',
+        Inline::Underlined { .. } => '+',
+        _ => return false,
+    };
+    // Phrases are parsed only at word boundaries and runs of delimiters must match.
+    let next = next.chars().next();
+    is_word(prev) || is_word(next) || prev == Some(delimiter) || next == Some(delimiter)
+}
+
+/// Checks that typographic glyph is made of characters around space, e.g. `2 x 3` or `Textile (TM)`,
+/// so line can't be broken there.
+fn is_glyph_around(before: char, after: &str) -> bool {
+    let after = after.chars().next();
+
+    before == '-' ||
+    before == 'x' && after.is_some_and(|ch| ch.is_ascii_digit()) ||
+    before.is_ascii_digit() && after == Some('x') ||
+    before.is_alphanumeric() && (after == Some('(') || after == Some('['))
+}
+
+/// Returns text of span, which is parsed from abbreviation without transcript, e.g. `ABBR`.
+fn abbreviation<'a>(element: &'a Inline) -> Option<&'a str> {
+    match *element {
+        Inline::Span { ref attributes, ref elements } if attributes.is_empty() => match elements[..] {
+            [Inline::Text(ref text)] if is_abbreviation(text) => Some(text),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Joins adjacent texts, which are parsed separately from rewrapped lines, e.g. after inline HTML.
+fn join_texts<'a>(elements: &[Inline<'a>]) -> Vec<Inline<'a>> {
+    let mut res: Vec<Inline> = Vec::with_capacity(elements.len());
+
+    for element in elements {
+        match (res.last_mut(), element) {
+            (Some(&mut Inline::Text(ref mut prev)), &Inline::Text(ref text)) => prev.to_mut().push_str(text),
+            _ => res.push(element.clone()),
+        }
+    }
+    res
+}
+
+/// Separates the next block by empty line. Text of extended block may already end with it.
+fn separate(text: &mut String) {
+    text.push_str(if text.ends_with('\n') { "\n" } else { "\n\n" });
+}
+
+/// Returns mode of block signature: extended `..`, if lines after the first one contain empty line, or `.`.
+fn lines_mode<S: AsRef<str>>(lines: &[S]) -> &'static str {
+    if lines.iter().skip(1).any(|line| line.as_ref().is_empty()) { ".." } else { "." }
+}
+
+fn alias_name(idx: usize) -> String {
+    format!("url{}", idx + 1)
+}
+
+/// Collects endnotes of lists of notes, which aren't written in place.
+fn collect_notes<'a, 'b>(blocks: &'b [Block<'a>], notes: &mut Vec<&'b Note<'a>>) {
+    for block in blocks {
+        match *block {
+            Block::Div { ref elements, .. } => collect_notes(elements, notes),
+            Block::NoteList { ref elements, .. } => {
+                for note in elements {
+                    if !notes.iter().any(|collected| collected.label == note.label) {
+                        notes.push(note);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_round_trip(text: &str) {
+        let blocks = parse(text);
+        assert_eq!(parse(to_textile(&blocks)), blocks, "{}", text);
+    }
+
+    #[test]
+    fn writes_blocks_correctly() {
+        assert_eq!(to_textile(&parse("h2(title)[en]. Heading\n\nbc.. fn main() {\n\n}")),
+                   "h2(title)[en]. Heading\n\nbc.. fn main() {\n\n}\n");
+        assert_eq!(to_textile(&parse("p{color:red}. Text\n\n* One\n** Two\n\n# Three")),
+                   "p{color: red}. Text\n\n* One\n** Two\n\n# Three\n");
+        assert_eq!(to_textile(&parse("- Term := Definition\n\n|_. Head|\n|(cls). Cell|")),
+                   "; Term\n: Definition\n\n|_. Head|\n|(cls). Cell|\n");
+        assert_round_trip("bq.:http://example.com Quote\n\nbq.. First\n\np. Second\n\nText");
+        assert_round_trip("###.. Comment\n\nnotextile. <b>Raw</b>\n\npre. Pre\n\n<div>\nHTML\n</div>");
+        assert_round_trip("div(box).. Text\n\n* Item\n\n- Term :=\nMulti\n\nline =:");
+        assert_round_trip("fig(pic). !image.png(Alt)! Caption\n\nfn1^. Footnote [1]\n\nText[#note]\n\nnote#note. Note\n\nnotelist:a^+.");
+        assert_round_trip("table(tbl).\n|=. Caption\n|:\\2. 100|\n|^.\n|\\2/2=~. Cell|\n|~.\n|-.\n(row). |Cell|");
+    }
+
+    #[test]
+    fn writes_attributes_correctly() {
+        assert_eq!(to_textile(&parse("p((>. Text")), "p((>. Text\n");
+        assert_eq!(to_textile(&parse("p(class#id){color:red}[fr]=. Text")), "p(class#id)[fr]{color: red}=. Text\n");
+        assert_round_trip("|<^. Left|>-. Right|=~. Center|<>. Justify|");
+        assert_round_trip("p{padding-left: 1em; text-align: right}. Text");
+    }
+
+    #[test]
+    fn writes_inline_elements_correctly() {
+        assert_eq!(to_textile(&parse("*strong* **b** _em_ __i__ -del- +ins+ ^sup^ ~sub~ ??cite?? %span% @code@")),
+                   "*strong* **b** _em_ __i__ -del- +ins+ ^sup^ ~sub~ ??cite?? %span% @code@\n");
+        assert_eq!(to_textile(&parse("a[*b*]c")), "a[*b*]c\n");
+        assert_eq!(to_textile(&parse("\"Link(Title)\":http://example.com \"$\":http://example.com")),
+                   "\"Link(Title)\":http://example.com \"$\":http://example.com\n");
+        assert_eq!(to_textile(&parse("[alias]http://example.com/(page)\n\n\"Link\":alias")),
+                   "[url1]http://example.com/(page)\n\n\"Link\":url1\n");
+        assert_round_trip("!>(cls)image.png 10x20(Alt)!:http://example.com ABC ABBR(Transcript) ==*raw*== [1] [2]! <b>HTML</b>");
+        assert_round_trip("Line\n break *bold\nline*");
+    }
+
+    #[test]
+    fn formats_text_correctly() {
+        assert_eq!(format("p{color:red}(cls). Text\n\n\n\n#  One\n##    Two", 80),
+                   "p(cls){color: red}. Text\n\n# One\n## Two\n");
+        assert_eq!(format("Some words\n are joined and *rewrapped* again", 16),
+                   "Some words are\n joined and\n *rewrapped*\n again\n");
+        assert_eq!(format("* Item with words\nLine break", 10), "* Item\n with\n words\nLine break\n");
+        // Glyphs are made of characters around spaces.
+        assert_eq!(format("Size 10 x 20 -- wide - Textile (TM)", 1), "Size\n 10 x 20\n -- wide\n - Textile (TM)\n");
+
+        let text = "h1. \"Heading\"\n\n- Term := Definition with words =: inside\n\nbq. Quote -- 'single' 2 x 3 (c) - 80's...";
+        let options = || RenderOptions { glyphs: Some(Glyphs::default()), ..RenderOptions::default() };
+        for width in 1..40 {
+            assert_eq!(render_with(format(text, width), options()), render_with(text, options()));
+        }
+    }
+
+    #[test]
+    fn formats_formatted_text_without_changes() {
+        let texts = [
+            ("p{color:red}(cls). Text\n\n\n\n#  One\n##    Two", 80),
+            ("Some words\n are joined and *rewrapped* again", 16),
+            ("* Item with words\nLine break", 10),
+            ("Size 10 x 20 -- wide - Textile (TM)", 1),
+            ("h1. \"Heading\"\n\n- Term := Definition with words =: inside\n\nbq. Quote -- 'single' 2 x 3 (c) - 80's...", 20),
+            // Wrapped text after inline element is parsed as separate texts.
+            ("x-->h1-)*\"!fig#+bqx<div>h1 div>^x", 20),
+            ("--> <!--_x:h1$-|:|^.|:<", 20),
+        ];
+
+        for &(text, width) in &texts {
+            let formatted = format(text, width);
+            assert_eq!(format(&*formatted, width), formatted, "{}", text);
+        }
+    }
+
+    #[test]
+    fn writes_elements_without_content_correctly() {
+        assert_round_trip("%[x]%");
+        assert_round_trip("\"{x}\":http://example.com");
+        assert_round_trip("![x]!");
+    }
+
+    #[test]
+    fn writes_unmatched_delimiters_correctly() {
+        assert_eq!(to_textile(&parse("=====]")), "=====]\n");
+        assert_eq!(to_textile(&parse("??a**]*")), "??a**]*\n");
+        assert_eq!(to_textile(&parse("# \n }")), "# \n }\n");
+        assert_round_trip("=====]");
+        assert_round_trip("??a**]*");
+        assert_round_trip("# \n }");
+        // Source of image starts with character of alignment.
+        assert_eq!(to_textile(&parse("!||fn1!")), "![ ]|fn1!\n");
+        assert_round_trip("!||fn1!");
+        assert_round_trip("!<=x!");
+    }
+}
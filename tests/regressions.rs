@@ -0,0 +1,118 @@
+//! Regression corpus of inputs, which caused panics or endless loops. Every public function
+//! must return for any of them and for any of their prefixes.
+
+extern crate textile;
+
+use textile::parser::{Parser, from_events, parse, parse_checked, parse_with_aliases, parse_with_spans};
+use textile::{render, render_restricted, render_with, RenderOptions};
+
+const CORPUS: &[&str] = &[
+    "",
+    "\n\n\n",
+    " ",
+    "p{color}. Text",
+    "p{foo}. Text",
+    "p{color:}. Text",
+    "p{;;}. Text",
+    "p{color: red. Text",
+    "p(#). Text",
+    "p[]. Text",
+    "h7. Not heading",
+    "h1{}. Heading",
+    "\"$\":http://127.0.0.1/path",
+    "\"$\":http://[::1]/",
+    "\"$\":mailto:",
+    "\"$\":",
+    "\"\":http://example.com",
+    "!!",
+    "!image.png 10x!",
+    "!(alt)!",
+    "!>!:",
+    "**Text*",
+    "*Text**",
+    "__Text_",
+    "***",
+    "* *",
+    "*\n*",
+    "**\n# item",
+    "# item\n## nested\n* mixed\n### deep",
+    "## orphan nested item",
+    "- term := definition\n-",
+    "; term\n: definition",
+    ":=",
+    "bq..",
+    "bq.. \n\n\n",
+    "bq.. Text\n\nh1. Heading",
+    "bc.. ",
+    "pre..\n\n",
+    "notextile..",
+    "###. ",
+    "###..\n\n\n",
+    "div.. \n\ndiv.. \n\n",
+    "fig. Not image",
+    "fig. !image.png!",
+    "fn1. ",
+    "fn1^. Footnote",
+    "note#a. ",
+    "notelist:.",
+    "[#a] [#b!] [1] [1!]",
+    "table.",
+    "table(#). \n|=. \n|:\\2 | \n|^. \n|_.",
+    "|",
+    "||",
+    "|\\99999999999999999999. a|",
+    "|/0. a|",
+    "|{}. a|",
+    "<div>",
+    "</div>",
+    "<div><div></div>",
+    "<!--",
+    "<hr/>",
+    "[alias]http://example.com\n\"Link\":alias",
+    "[1]http://example.com",
+    "[*]",
+    "[\"a\":b",
+    "ABC(",
+    "==",
+    "@@",
+    "??",
+    "?? ??",
+    "é*é*é _ü_ ^ä^",
+    "\u{301}*\u{301}*",
+    "\t\r\n\r\n",
+    "\r",
+    "p\u{a0}. Text",
+];
+
+fn check(text: &str) {
+    let text = text.to_string();
+    render(text.clone());
+    render_restricted(text.clone());
+    render_with(text.clone(), RenderOptions { comments: true, html5: true, ..RenderOptions::default() });
+    parse(text.clone());
+    parse_with_aliases(text.clone());
+    parse_with_spans(text.clone());
+    from_events(Parser::new(&text));
+    parse_checked(text);
+}
+
+#[test]
+fn handles_corpus_without_panics() {
+    for text in CORPUS {
+        for (idx, _) in text.char_indices().skip(1) {
+            check(&text[..idx]);
+        }
+        check(text);
+    }
+}
+
+#[test]
+fn handles_concatenated_corpus_without_panics() {
+    for first in CORPUS {
+        for second in CORPUS {
+            let text = format!("{}\n{}", first, second);
+            render(text.clone());
+            parse_checked(text);
+        }
+    }
+}
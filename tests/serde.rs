@@ -0,0 +1,56 @@
+//! JSON shape of parsed documents with `serde` feature.
+#![cfg(feature = "serde")]
+
+extern crate serde_json;
+extern crate textile;
+
+use textile::parser::{parse, BlockElements};
+
+#[test]
+fn serializes_elements_into_documented_json() {
+    let blocks = parse("p(intro). Some *text*\nline");
+
+    assert_eq!(
+        serde_json::to_value(&blocks).unwrap(),
+        serde_json::json!([
+            {
+                "type": "paragraph",
+                "content": {
+                    "attributes": { "class": "intro" },
+                    "elements": [
+                        { "type": "text", "content": "Some " },
+                        {
+                            "type": "bold",
+                            "content": {
+                                "attributes": {},
+                                "elements": [{ "type": "text", "content": "text" }],
+                                "tag_type": "strong"
+                            }
+                        },
+                        { "type": "break" },
+                        { "type": "text", "content": "line" }
+                    ],
+                    "starts_with_p": true
+                }
+            }
+        ])
+    );
+}
+
+#[test]
+fn deserializes_serialized_elements_correctly() {
+    let text = "h1. Title\n\n\
+                bc.. code\n\n\
+                more\n\n\
+                * item\n** nested\n\n\
+                - term := definition\n\n\
+                |^.\n|_. Header|\n|Data \"link\":http://example.com|\n\n\
+                Text[#note] and footnote[1], !image.png!\n\n\
+                note#note. Endnote\n\n\
+                notelist^.\n\n\
+                fn1. Footnote";
+    let blocks = parse(text);
+    let json = serde_json::to_string(&blocks).unwrap();
+
+    assert_eq!(serde_json::from_str::<BlockElements>(&json).unwrap(), blocks);
+}